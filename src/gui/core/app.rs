@@ -1,35 +1,71 @@
 //! Module defining the application structure: messages, updates, subscriptions.
-use iced::widget::{TextInput, Button, Text, Row, Container};
-use iced::{executor, Application, Command, Element, Theme, Length};
-use serde_json::Value;
+use iced::widget::{TextInput, Button, Text, Row, Column, Container, qr_code};
+use iced::{executor, Application, Command, Element, Subscription, Theme, Length};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::time::Duration;
 use zeroize::Zeroize;
 
 use crate::gui::core::{
     message::Message,
+    preferences::Preferences,
 };
 
-use crate::gui::pages::details_page::{PageMode, EntryType};
+use crate::gui::pages::details_page::{PageMode, EntryType, DetailsContext};
 use crate::gui::pages::{
+    api_credentials_page,
+    bank_accounts_page,
     cards_page,
     details_page,
+    favorites_page,
+    generator_page,
     identities_page,
-    nav_page,
+    nav_page::{self, NavContext},
+    notes_page,
     passwords_page,
-    profile_page,
+    profile_page::{self, TransferContext},
+    search_page,
+    ssh_keys_page,
+    wifi_networks_page,
 };
 
 use crate::gui::styles::types::element_type::ElementType;
 use crate::gui::styles::types::style_tuple::StyleTuple;
 use crate::gui::styles::types::style_type;
-use crate::secure::encrypt::{read_data, encrypt_sensitive_fields, decrypt_sensitive_fields, write_data};
+use crate::import::{self, ImportFormat, ImportOptions, ImportPreview};
+use crate::import::kdbx::KdbxCipher;
+use crate::import::keepass;
+use crate::secure::attachment;
+use crate::secure::encrypt::{read_data, encrypt_sensitive_fields, decrypt_sensitive_fields, write_data, vault_exists};
+use crate::secure::generator::{self, GeneratorOptions};
+use crate::secure::ssh::{self, SshKeyMaterial};
+use crate::secure::strength;
+use crate::secure::totp::{self, OtpAuth, OtpKind};
+use crate::vault::custom_field::{custom_fields, move_custom_field, totp_uri, update_custom_fields, CustomField, CustomFieldType};
+use crate::vault::document::{documents, update_documents, DocumentType, IdentityDocument};
+use crate::vault::entry::{self, now};
+use crate::vault::expiry::ExpiryWindow;
+use crate::vault::folder;
+use crate::vault::schema::{self, FieldKind};
+use crate::vault::search::EntryFilter;
+use crate::vault::vcard;
+use crate::vault::wifi::{wifi_payload, WifiSecurity};
 
 // An enumeration of the different views in the application
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Pages {
+    ApiCredentialsPage,
+    BankAccountsPage,
     CardsPage,
+    FavoritesPage,
+    GeneratorPage,
     IdentitiesPage,
+    NotesPage,
     PasswordsPage,
     ProfilePage,
+    SearchPage,
+    SshKeysPage,
+    WifiNetworksPage,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -44,11 +80,47 @@ pub struct KeyboltApp {
     pub current_page: Pages,
     pub current_style: style_type::StyleType,
     pub passphrase: String,
+    pub passphrase_confirm: String,
+    pub vault_exists: bool,
+    pub login_error: String,
     pub entries: Value,
     pub selected_entry_id: i32,
     pub current_entry_edits: Value,
     pub current_entry_mode: details_page::PageMode,
     pub current_entry_type: EntryType,
+    pub revealed_entry: Option<Value>,
+    pub revealed_qr: Option<qr_code::State>,
+    pub ssh_path: String,
+    pub ssh_passphrase: String,
+    pub ssh_status: String,
+    pub attachment_path: String,
+    pub attachment_status: String,
+    pub transfer_path: String,
+    pub transfer_status: String,
+    pub import_format: ImportFormat,
+    pub import_preview: Option<ImportPreview>,
+    // Counts preview requests, only the result of the latest one is shown
+    pub import_request: u64,
+    // Vault an import running in the background started from
+    pub import_base: Option<Value>,
+    pub skip_duplicates: bool,
+    pub transfer_password: String,
+    pub kdbx_cipher: KdbxCipher,
+    pub generator_options: GeneratorOptions,
+    pub generated_password: String,
+    pub show_entry_generator: bool,
+    pub current_time: u64,
+    pub search_query: String,
+    pub preferences: Preferences,
+    pub tag_input: String,
+    pub show_tags: bool,
+    pub selected_tags: BTreeSet<String>,
+    pub show_folders: bool,
+    pub selected_folder: Option<u64>,
+    pub folder_input: String,
+    pub folder_error: String,
+    // Waiting for the user to confirm deleting the selected folder with its entries
+    pub confirm_folder_delete: bool,
 }
 
 impl Application for KeyboltApp {
@@ -58,16 +130,51 @@ impl Application for KeyboltApp {
     type Flags = ();
 
     fn new(_flags: Self::Flags) -> (Self, Command<Message>) {
+        let generator_options = GeneratorOptions::default();
+        let generated_password = generator::generate(&generator_options);
         (KeyboltApp {
             login_state: LoginState::LoggedOut,
             current_page: Pages::PasswordsPage,
             current_style: style_type::StyleType::Default,
             passphrase: String::new(),
+            passphrase_confirm: String::new(),
+            vault_exists: vault_exists(),
+            login_error: String::new(),
             entries: Value::Null,
             selected_entry_id: -1,
             current_entry_edits: Value::Null,
             current_entry_mode: PageMode::Closed,
             current_entry_type: EntryType::Passwords,
+            revealed_entry: None,
+            revealed_qr: None,
+            ssh_path: String::new(),
+            ssh_passphrase: String::new(),
+            ssh_status: String::new(),
+            attachment_path: String::new(),
+            attachment_status: String::new(),
+            transfer_path: String::new(),
+            transfer_status: String::new(),
+            import_format: ImportFormat::BrowserCsv,
+            import_preview: None,
+            import_request: 0,
+            import_base: None,
+            skip_duplicates: true,
+            transfer_password: String::new(),
+            kdbx_cipher: KdbxCipher::Aes256,
+            generator_options,
+            generated_password,
+            show_entry_generator: false,
+            current_time: now(),
+            search_query: String::new(),
+            preferences: Preferences::load(),
+            tag_input: String::new(),
+            show_tags: true,
+            selected_tags: BTreeSet::new(),
+            show_folders: true,
+            selected_folder: None,
+            folder_input: String::new(),
+            folder_error: String::new(),
+            confirm_folder_delete: false,
         }, Command::none())
     }
 
@@ -82,13 +189,46 @@ impl Application for KeyboltApp {
                 self.current_entry_type = match page {
                     Pages::CardsPage => EntryType::Cards,
                     Pages::IdentitiesPage => EntryType::Identities,
+                    Pages::NotesPage => EntryType::Notes,
+                    Pages::SshKeysPage => EntryType::SshKeys,
+                    Pages::ApiCredentialsPage => EntryType::ApiCredentials,
+                    Pages::BankAccountsPage => EntryType::BankAccounts,
+                    Pages::WifiNetworksPage => EntryType::WifiNetworks,
                     _ => EntryType::Passwords,
                 };
                 self.selected_entry_id = -1;
                 self.current_entry_mode = PageMode::Closed;
+                self.revealed_entry = None;
             },
             Message::ChangeStyle(style) => self.current_style = style,
+            Message::ChangeExpiryWindow(days) => {
+                self.preferences.expiry_warning_days = days;
+                if let Err(e) = self.preferences.save() {
+                    println!("Error saving preferences: {:?}", e);
+                }
+            },
             Message::PasswordInputChanged(passphrase) => self.passphrase = passphrase,
+            Message::PasswordConfirmInputChanged(passphrase) => self.passphrase_confirm = passphrase,
+            // No vault yet, create an empty one encrypted with the new passphrase
+            Message::PasswordInputSubmit if !self.vault_exists => {
+                if self.passphrase.is_empty() {
+                    self.login_error = String::from("Passphrase can't be empty");
+                } else if self.passphrase != self.passphrase_confirm {
+                    self.login_error = String::from("Passphrases don't match");
+                } else {
+                    let data = json!({ "passwords": [], "identities": [], "cards": [], "notes": [], "ssh_keys": [], "api_credentials": [], "bank_accounts": [], "wifi_networks": [], "folders": [] });
+                    match write_data(&self.passphrase, data.clone()) {
+                        Ok(_) => {
+                            self.entries = data;
+                            self.vault_exists = true;
+                            self.login_error.clear();
+                            self.passphrase_confirm.zeroize();
+                            self.login_state = LoginState::LoggedIn;
+                        },
+                        Err(e) => self.login_error = format!("Error creating vault: {}", e),
+                    }
+                }
+            },
             Message::PasswordInputSubmit => {
                 if self.login_state != LoginState::LoggingIn {
                     self.login_state = LoginState::LoggingIn;
@@ -96,16 +236,46 @@ impl Application for KeyboltApp {
                     match read_data(&self.passphrase) {
                         Ok(data) => {
                             self.entries = data;
+                            entry::normalize_vault(&mut self.entries);
+                            // Blobs are only pruned against the vault as saved on disk, files of
+                            // removals that were never saved are still referenced there
+                            if let Err(e) = attachment::prune_attachments(&self.entries) {
+                                println!("Error removing attachments: {:?}", e);
+                            }
                             // println!("Data: {:?}", self.entries);
+                            self.login_error.clear();
                             self.login_state = LoginState::LoggedIn;
                         },
                         Err(e) => {
                             println!("Error reading data: {:?}", e);
+                            self.login_error = String::from("Couldn't unlock the vault, check your passphrase");
                             self.login_state = LoginState::LoggedOut;
                         },
                     }
                 }
             },
+            Message::Tick => self.current_time = now(),
+            Message::CopyToClipboard(contents) => return iced::clipboard::write(contents),
+            // Move an HOTP entry on to its next code, the counter is stored back into the otpauth URI
+            Message::IncrementHotpCounter => {
+                let uri = self.current_entry_edits["otpauth"].as_str().unwrap_or("").to_string();
+                // A counter at its maximum has no next code, the URI is left as it is
+                if let Ok(OtpAuth { kind: OtpKind::Hotp { counter }, .. }) = OtpAuth::parse(&uri) {
+                    let Some(next) = counter.checked_add(1) else {
+                        return Command::none();
+                    };
+                    let uri = Value::String(totp::with_counter(&uri, next));
+                    self.current_entry_edits["otpauth"] = uri.clone();
+                    if self.current_entry_mode == PageMode::View {
+                        self.entries[self.current_entry_type.as_str()][self.selected_entry_id as usize]["otpauth"] = uri;
+                    }
+                }
+            },
+            // A draft that was never saved has nothing to go back to
+            Message::ChangeEntryMode(mode) if mode != PageMode::Edit && self.selected_entry_id < 0 => {
+                self.current_entry_mode = PageMode::Closed;
+                self.revealed_entry = None;
+            },
             Message::ChangeEntryMode(mode) => {
                 if mode == PageMode::Edit {
                     decrypt_sensitive_fields(&self.passphrase, &mut self.current_entry_edits, self.current_entry_type);
@@ -113,64 +283,600 @@ impl Application for KeyboltApp {
                     encrypt_sensitive_fields(&self.passphrase, &mut self.current_entry_edits, self.current_entry_type);
                 }
                 self.current_entry_mode = mode;
+                self.revealed_entry = None;
             },
             Message::SaveEntryEdits => {
                 self.current_entry_mode = PageMode::View;
+                self.revealed_entry = None;
+                self.ssh_status.clear();
+                self.attachment_status.clear();
+                entry::touch_modified(&mut self.current_entry_edits);
+                schema::prepare_for_save(self.current_entry_type, &mut self.current_entry_edits);
                 encrypt_sensitive_fields(&self.passphrase, &mut self.current_entry_edits, self.current_entry_type);
-                self.entries[self.current_entry_type.as_str()][self.selected_entry_id as usize] = self.current_entry_edits.clone();
+                if self.selected_entry_id >= 0 {
+                    self.entries[self.current_entry_type.as_str()][self.selected_entry_id as usize] = self.current_entry_edits.clone();
+                } else if let Some(entries) = self.entries[self.current_entry_type.as_str()].as_array_mut() {
+                    entries.push(self.current_entry_edits.clone());
+                    self.selected_entry_id = entries.len() as i32 - 1;
+                }
                 //TODO UNCOMMENT TO SAVE CHANGES TO FILE
                 // write_data(&self.passphrase, self.entries.clone());
             },
             Message::SelectEntry(entry_id) => {
                 self.selected_entry_id = entry_id;
+                entry::touch_used(&mut self.entries[self.current_entry_type.as_str()][entry_id as usize]);
                 self.current_entry_edits = self.entries[self.current_entry_type.as_str()][entry_id as usize].clone();
                 println!("{:?}", entry_id);
                 println!("{:?}", self.entries[self.current_entry_type.as_str()][entry_id as usize]);
                 self.current_entry_mode = PageMode::View;
+                self.revealed_entry = None;
+                self.ssh_status.clear();
+                self.attachment_status.clear();
+            },
+            // Secrets are decrypted only for the copy, entries in edit mode already are
+            Message::CopySecretField(field) => {
+                let mut entry = self.current_entry_edits.clone();
+                if self.current_entry_mode != PageMode::Edit {
+                    if let Err(e) = decrypt_sensitive_fields(&self.passphrase, &mut entry, self.current_entry_type) {
+                        println!("Error decrypting entry: {:?}", e);
+                        return Command::none();
+                    }
+                }
+                return iced::clipboard::write(entry[field].as_str().unwrap_or("").to_string());
+            },
+            Message::CopyCustomField(index) => {
+                let mut entry = self.current_entry_edits.clone();
+                if self.current_entry_mode != PageMode::Edit {
+                    if let Err(e) = decrypt_sensitive_fields(&self.passphrase, &mut entry, self.current_entry_type) {
+                        println!("Error decrypting entry: {:?}", e);
+                        return Command::none();
+                    }
+                }
+                if let Some(field) = custom_fields(&entry).get(index) {
+                    return iced::clipboard::write(field.value.clone());
+                }
+            },
+            Message::CopyDocumentNumber(index) => {
+                let mut entry = self.current_entry_edits.clone();
+                if self.current_entry_mode != PageMode::Edit {
+                    if let Err(e) = decrypt_sensitive_fields(&self.passphrase, &mut entry, self.current_entry_type) {
+                        println!("Error decrypting entry: {:?}", e);
+                        return Command::none();
+                    }
+                }
+                if let Some(document) = documents(&entry).get(index) {
+                    return iced::clipboard::write(document.number.clone());
+                }
+            },
+            // Search results and favorites can be of any entry type
+            Message::SelectEntryOfType(entry_type, entry_id) => {
+                self.current_entry_type = entry_type;
+                self.selected_entry_id = entry_id;
+                entry::touch_used(&mut self.entries[entry_type.as_str()][entry_id as usize]);
+                self.current_entry_edits = self.entries[entry_type.as_str()][entry_id as usize].clone();
+                self.current_entry_mode = PageMode::View;
+                self.revealed_entry = None;
+                self.ssh_status.clear();
+                self.attachment_status.clear();
+            },
+            // New entries open straight in edit mode, inside the folder being browsed
+            // The new entry is only a draft in the edits until it is saved, selected_entry_id -1 marks it
+            Message::NewEntry => {
+                self.selected_entry_id = -1;
+                self.current_entry_edits = entry::new_entry(self.current_entry_type, self.selected_folder);
+                self.current_entry_mode = PageMode::Edit;
+                self.revealed_entry = None;
+                self.ssh_status.clear();
+                self.attachment_status.clear();
+            },
+            // Decrypt a copy of the entry to show its secrets in view mode, the stored entry stays encrypted
+            Message::ToggleRevealSecrets if self.revealed_entry.is_some() => self.revealed_entry = None,
+            Message::ToggleRevealSecrets => {
+                let mut revealed = self.current_entry_edits.clone();
+                match decrypt_sensitive_fields(&self.passphrase, &mut revealed, self.current_entry_type) {
+                    Ok(_) => {
+                        // Wi-Fi networks are shared with a QR code of the WIFI: payload
+                        self.revealed_qr = if self.current_entry_type == EntryType::WifiNetworks {
+                            qr_code::State::new(wifi_payload(
+                                revealed["ssid"].as_str().unwrap_or(""),
+                                WifiSecurity::from_stored(revealed["security"].as_str().unwrap_or("")),
+                                revealed["passphrase"].as_str().unwrap_or(""),
+                                revealed["hidden"].as_bool().unwrap_or(false),
+                            )).ok()
+                        } else {
+                            None
+                        };
+                        self.revealed_entry = Some(revealed);
+                    },
+                    Err(e) => println!("Error decrypting entry: {:?}", e),
+                }
+            },
+            // Toggling from the view mode saves the entry right away
+            Message::ToggleFavorite(favorite) => {
+                self.current_entry_edits["favorite"] = Value::Bool(favorite);
+                if self.current_entry_mode == PageMode::View {
+                    self.entries[self.current_entry_type.as_str()][self.selected_entry_id as usize]["favorite"] = Value::Bool(favorite);
+                }
+            },
+            Message::MoveToFolder(choice) => {
+                folder::set_entry_folder(&mut self.current_entry_edits, choice.id);
+                if self.current_entry_mode == PageMode::View {
+                    let entry = &mut self.entries[self.current_entry_type.as_str()][self.selected_entry_id as usize];
+                    folder::set_entry_folder(entry, choice.id);
+                    entry::touch_modified(entry);
+                }
+            },
+            Message::UpdateSearchQuery(query) => self.search_query = query,
+            // Sort preferences are kept per entry type and saved right away
+            Message::ChangeSortOrder(order) => {
+                let mut sort = self.preferences.sort_for(self.current_entry_type);
+                sort.order = order;
+                self.preferences.sort.insert(self.current_entry_type.as_str().to_string(), sort);
+                if let Err(e) = self.preferences.save() {
+                    println!("Error saving preferences: {:?}", e);
+                }
+            },
+            Message::ChangeGrouping(grouping) => {
+                let mut sort = self.preferences.sort_for(self.current_entry_type);
+                sort.grouping = grouping;
+                self.preferences.sort.insert(self.current_entry_type.as_str().to_string(), sort);
+                if let Err(e) = self.preferences.save() {
+                    println!("Error saving preferences: {:?}", e);
+                }
+            },
+            // Messages for tags, typing a comma adds the tag right away
+            Message::UpdateTagInput(input) => {
+                match input.strip_suffix(',') {
+                    Some(tag) => {
+                        entry::add_tag(&mut self.current_entry_edits, tag);
+                        self.tag_input.clear();
+                    },
+                    None => self.tag_input = input,
+                }
+            },
+            Message::AddTag(tag) => {
+                entry::add_tag(&mut self.current_entry_edits, &tag);
+                self.tag_input.clear();
+            },
+            Message::RemoveTag(tag) => entry::remove_tag(&mut self.current_entry_edits, &tag),
+            Message::ToggleTagSection => self.show_tags = !self.show_tags,
+            Message::ToggleTagFilter(tag) => {
+                if self.selected_tags.contains(&tag) {
+                    self.selected_tags.remove(&tag);
+                } else {
+                    self.selected_tags.insert(tag);
+                }
+            },
+
+            // Messages for managing folders
+            Message::ToggleFolderSection => self.show_folders = !self.show_folders,
+            Message::SelectFolder(folder) => {
+                self.selected_folder = folder;
+                self.folder_error.clear();
+                self.confirm_folder_delete = false;
+            },
+            Message::UpdateFolderInput(input) => self.folder_input = input,
+            Message::CreateFolder => {
+                match folder::create_folder(&mut self.entries, &self.folder_input, self.selected_folder) {
+                    Ok(id) => {
+                        self.selected_folder = Some(id);
+                        self.folder_input.clear();
+                        self.folder_error.clear();
+                    },
+                    Err(e) => self.folder_error = e.to_string(),
+                }
+            },
+            Message::RenameFolder => {
+                if let Some(id) = self.selected_folder {
+                    match folder::rename_folder(&mut self.entries, id, &self.folder_input) {
+                        Ok(_) => {
+                            self.folder_input.clear();
+                            self.folder_error.clear();
+                        },
+                        Err(e) => self.folder_error = e.to_string(),
+                    }
+                }
+            },
+            Message::ConfirmFolderDelete(ask) => self.confirm_folder_delete = ask,
+            // Entries are only deleted once the user confirmed it
+            Message::DeleteFolder(true) if !self.confirm_folder_delete => {},
+            Message::DeleteFolder(delete_entries) => {
+                self.confirm_folder_delete = false;
+                if let Some(id) = self.selected_folder {
+                    let parent = folder::folders(&self.entries).iter().find(|f| f.id == id).and_then(|f| f.parent);
+                    match folder::delete_folder(&mut self.entries, id, delete_entries) {
+                        Ok(_) => {
+                            self.selected_folder = parent;
+                            self.folder_error.clear();
+                            // Entry positions shift when entries are deleted, so drop the selection
+                            self.selected_entry_id = -1;
+                            self.current_entry_mode = PageMode::Closed;
+                            self.revealed_entry = None;
+                        },
+                        Err(e) => self.folder_error = e.to_string(),
+                    }
+                }
+            },
+
+            // Field edits, checked against the entry type's schema
+            Message::UpdateField { entry_type, field, value } if entry_type == self.current_entry_type => {
+                if let Some(spec) = schema::field(entry_type, field) {
+                    self.current_entry_edits[field] = value;
+                    if let Some(on_change) = spec.on_change {
+                        on_change(&mut self.current_entry_edits);
+                    }
+                }
+            },
+
+            // Messages for SSH key entries, keys are only generated or imported while editing
+            Message::UpdateSshKeyPath(input) => self.ssh_path = input,
+            Message::UpdateSshKeyPassphrase(input) => self.ssh_passphrase = input,
+            Message::GenerateSshKey if self.current_entry_mode == PageMode::Edit => {
+                let result = ssh::generate_ed25519(self.current_entry_edits["comment"].as_str().unwrap_or(""));
+                self.apply_ssh_key(result, "Generated a new Ed25519 key");
+            },
+            Message::ImportSshKey if self.current_entry_mode == PageMode::Edit => {
+                let result = ssh::import_private_key(&self.ssh_path, &self.ssh_passphrase);
+                self.ssh_passphrase.zeroize();
+                self.apply_ssh_key(result, "Imported the private key");
+            },
+            Message::ExportSshPublicKey => {
+                self.ssh_status = match ssh::export_public_key(self.current_entry_edits["public_key"].as_str().unwrap_or(""), &self.ssh_path) {
+                    Ok(_) => format!("Public key saved to {}", self.ssh_path.trim()),
+                    Err(e) => e.to_string(),
+                };
+            },
+
+            // Imported identities land in the folder being browsed
+            Message::UpdateTransferPath(input) => self.transfer_path = input,
+            Message::ImportVcards => {
+                self.transfer_status = match vcard::import_vcards(&self.transfer_path, self.selected_folder) {
+                    Ok(identities) => {
+                        let count = identities.len();
+                        if let Some(entries) = self.entries["identities"].as_array_mut() {
+                            entries.extend(identities);
+                        }
+                        format!("Imported {} identities", count)
+                    },
+                    Err(e) => e.to_string(),
+                };
+            },
+            Message::ExportVcards(all) => {
+                let identities: Vec<Value> = if all {
+                    self.entries["identities"].as_array().cloned().unwrap_or_default()
+                } else if self.current_entry_type == EntryType::Identities && self.selected_entry_id >= 0 {
+                    vec![self.entries["identities"][self.selected_entry_id as usize].clone()]
+                } else {
+                    Vec::new()
+                };
+                self.transfer_status = match vcard::export_vcards(&identities, &self.transfer_path) {
+                    Ok(count) => format!("Exported {} identities to {}", count, self.transfer_path.trim()),
+                    Err(e) => e.to_string(),
+                };
+            },
+
+            Message::ChangeImportFormat(format) => {
+                self.import_format = format;
+                self.import_preview = None;
+                // A preview still being read is for the old format
+                self.import_request += 1;
+            },
+            Message::PreviewImport => {
+                self.import_preview = None;
+                self.import_request += 1;
+                self.transfer_status = format!("Reading the {} file...", self.import_format);
+                // Reading can take a while, e.g. gpg runs once per pass file and may wait for its pinentry,
+                // so it runs off the UI thread
+                let (request, format, folder) = (self.import_request, self.import_format, self.selected_folder);
+                let (path, mut password, gpg_command) = (self.transfer_path.clone(), self.transfer_password.clone(), self.preferences.gpg_command.clone());
+                return Command::perform(
+                    async move {
+                        let options = ImportOptions { password: &password, gpg_command: &gpg_command };
+                        let preview = import::preview_import(format, &path, &options, folder).map_err(|e| e.to_string());
+                        password.zeroize();
+                        (request, format, preview)
+                    },
+                    |(request, format, preview)| Message::ImportPreviewed(request, format, preview),
+                );
+            },
+            // Previews of a cancelled or replaced request are dropped
+            Message::ImportPreviewed(request, format, _) if request != self.import_request || format != self.import_format => {},
+            Message::ImportPreviewed(_, _, result) => {
+                self.transfer_status = match result {
+                    Ok(mut preview) => {
+                        // Flagged now, the vault may have changed while the file was read
+                        preview.mark_duplicates(&self.entries);
+                        let status = format!("{} entries found, {} already in the vault, {} rows skipped", preview.entries.len(), preview.duplicates(), preview.skipped.len());
+                        self.import_preview = Some(preview);
+                        status
+                    },
+                    Err(e) => e,
+                };
+            },
+            Message::ToggleSkipDuplicates(skip) => self.skip_duplicates = skip,
+            Message::ConfirmImport => {
+                if let Some(preview) = self.import_preview.take() {
+                    let (format, summary, skipped) = (preview.format, preview.summary(self.skip_duplicates), preview.skipped.len());
+                    self.transfer_status = format!("Importing {} entries...", preview.entries.len());
+                    // Every secret is encrypted with its own key derivation, so the entries are added
+                    // to a copy of the vault in the background and swapped in once done
+                    let (mut passphrase, mut vault, skip_duplicates) = (self.passphrase.clone(), self.entries.clone(), self.skip_duplicates);
+                    self.import_base = Some(self.entries.clone());
+                    return Command::perform(
+                        async move {
+                            let imported = import::apply_import(&passphrase, &mut vault, preview, skip_duplicates)
+                                .map(|added| (vault, format!("Imported {} entries from the {} file ({}), {} records skipped", added, format, summary, skipped)))
+                                .map_err(|e| e.to_string());
+                            passphrase.zeroize();
+                            imported
+                        },
+                        Message::ImportApplied,
+                    );
+                }
+            },
+            Message::ImportApplied(result) => {
+                let base = self.import_base.take();
+                self.transfer_status = match result {
+                    // Edits made meanwhile would be lost by the swap
+                    Ok(_) if base.as_ref() != Some(&self.entries) => String::from("The vault changed while importing, nothing was added. Preview the file again to import it."),
+                    Ok((vault, status)) => {
+                        self.entries = vault;
+                        status
+                    },
+                    Err(e) => e,
+                };
+            },
+            Message::CancelImport => {
+                self.import_preview = None;
+                self.import_request += 1;
+                self.transfer_status.clear();
+            },
+            Message::UpdateTransferPassword(input) => self.transfer_password = input,
+            Message::ChangeKdbxCipher(cipher) => self.kdbx_cipher = cipher,
+            Message::UpdateGpgCommand(input) => {
+                self.preferences.gpg_command = input;
+                if let Err(e) = self.preferences.save() {
+                    println!("Error saving preferences: {:?}", e);
+                }
+            },
+            Message::ExportKdbx => {
+                self.transfer_status = format!("Writing {}...", self.transfer_path.trim());
+                // The Argon2 key derivation takes seconds, so the database is written off the UI thread
+                let (cipher, vault, path) = (self.kdbx_cipher, self.entries.clone(), self.transfer_path.clone());
+                let (mut passphrase, mut password) = (self.passphrase.clone(), self.transfer_password.clone());
+                return Command::perform(
+                    async move {
+                        let exported = keepass::export_kdbx(&passphrase, &vault, &path, &password, cipher)
+                            .map(|count| format!("Exported {} entries to {}", count, path.trim()))
+                            .map_err(|e| e.to_string());
+                        passphrase.zeroize();
+                        password.zeroize();
+                        exported
+                    },
+                    Message::KdbxExported,
+                );
+            },
+            Message::KdbxExported(result) => self.transfer_status = result.unwrap_or_else(|e| e),
+
+            // Messages for attachments, files are added and removed while editing
+            Message::UpdateAttachmentPath(input) => self.attachment_path = input,
+            Message::AddAttachment if self.current_entry_mode == PageMode::Edit => {
+                self.attachment_status = match attachment::add_attachment(&self.passphrase, &mut self.entries, &mut self.current_entry_edits, &self.attachment_path, now()) {
+                    Ok(added) => {
+                        self.attachment_path.clear();
+                        format!("Attached {}", added.name)
+                    },
+                    Err(e) => e.to_string(),
+                };
+            },
+            Message::SaveAttachment(index) => {
+                if let Some(saved) = attachment::attachments(&self.current_entry_edits).get(index) {
+                    self.attachment_status = match attachment::save_attachment(&self.passphrase, saved, &self.attachment_path) {
+                        Ok(path) => format!("Saved to {}", path.display()),
+                        Err(e) => e.to_string(),
+                    };
+                }
+            },
+            Message::RemoveAttachment(index) if self.current_entry_mode == PageMode::Edit => {
+                let mut attachments = attachment::attachments(&self.current_entry_edits);
+                if index < attachments.len() {
+                    let removed = attachments.remove(index);
+                    attachment::set_attachments(&mut self.current_entry_edits, &attachments);
+                    self.attachment_status = format!("Removed {}, save to delete it", removed.name);
+                }
+            },
+
+            // Messages for custom fields, shared by every entry type
+            Message::AddCustomField => update_custom_fields(&mut self.current_entry_edits, |fields| {
+                fields.push(CustomField { label: String::new(), field_type: CustomFieldType::Text, value: String::new() });
+            }),
+            Message::UpdateCustomFieldLabel(index, input) => update_custom_fields(&mut self.current_entry_edits, |fields| {
+                if let Some(field) = fields.get_mut(index) {
+                    field.label = input;
+                }
+            }),
+            Message::UpdateCustomFieldType(index, field_type) => update_custom_fields(&mut self.current_entry_edits, |fields| {
+                if let Some(field) = fields.get_mut(index) {
+                    field.field_type = field_type;
+                }
+            }),
+            Message::UpdateCustomFieldValue(index, input) => update_custom_fields(&mut self.current_entry_edits, |fields| {
+                if let Some(field) = fields.get_mut(index) {
+                    field.value = input;
+                }
+            }),
+            Message::MoveCustomField(index, up) => update_custom_fields(&mut self.current_entry_edits, |fields| {
+                move_custom_field(fields, index, up);
+            }),
+            Message::RemoveCustomField(index) => update_custom_fields(&mut self.current_entry_edits, |fields| {
+                if index < fields.len() {
+                    fields.remove(index);
+                }
+            }),
+
+            // Messages for identity documents
+            Message::AddDocument => update_documents(&mut self.current_entry_edits, |documents| {
+                documents.push(IdentityDocument::new(DocumentType::Passport));
+            }),
+            Message::UpdateDocumentType(index, document_type) => update_documents(&mut self.current_entry_edits, |documents| {
+                if let Some(document) = documents.get_mut(index) {
+                    document.document_type = document_type;
+                }
+            }),
+            Message::UpdateDocument(index, field, input) => update_documents(&mut self.current_entry_edits, |documents| {
+                if let Some(document) = documents.get_mut(index) {
+                    document.set(field, input);
+                }
+            }),
+            Message::RemoveDocument(index) => update_documents(&mut self.current_entry_edits, |documents| {
+                if index < documents.len() {
+                    documents.remove(index);
+                }
+            }),
+
+            // Messages for the password generator, every option change regenerates the password
+            Message::ChangeGeneratorMode(mode) => {
+                self.generator_options.mode = mode;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::UpdateGeneratorLength(length) => {
+                self.generator_options.length = length;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::ToggleGeneratorLowercase(enabled) => {
+                self.generator_options.lowercase = enabled;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::ToggleGeneratorUppercase(enabled) => {
+                self.generator_options.uppercase = enabled;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::ToggleGeneratorDigits(enabled) => {
+                self.generator_options.digits = enabled;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::ToggleGeneratorSymbols(enabled) => {
+                self.generator_options.symbols = enabled;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::ToggleGeneratorExcludeAmbiguous(enabled) => {
+                self.generator_options.exclude_ambiguous = enabled;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::ToggleGeneratorRequireEachClass(enabled) => {
+                self.generator_options.require_each_class = enabled;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::UpdateGeneratorWordCount(word_count) => {
+                self.generator_options.word_count = word_count;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::UpdateGeneratorSeparator(separator) => {
+                self.generator_options.separator = separator;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::ToggleGeneratorCapitalize(enabled) => {
+                self.generator_options.capitalize = enabled;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::ToggleGeneratorIncludeNumber(enabled) => {
+                self.generator_options.include_number = enabled;
+                self.generated_password = generator::generate(&self.generator_options);
+            },
+            Message::RegeneratePassword => self.generated_password = generator::generate(&self.generator_options),
+            Message::CopyGeneratedPassword => return iced::clipboard::write(self.generated_password.clone()),
+            Message::ToggleEntryGenerator(show) => self.show_entry_generator = show,
+            Message::GenerateEntryPassword if self.current_entry_mode == PageMode::Edit => {
+                self.current_entry_edits["password"] = Value::String(generator::generate(&self.generator_options));
             },
-            // Messages for updating password entries
-            Message::UpdatePasswordTitle(input) => self.current_entry_edits["title"] = Value::String(input),
-            Message::UpdatePasswordUrl(input) => self.current_entry_edits["url"] = Value::String(input),
-            Message::UpdatePasswordUsername(input) => self.current_entry_edits["username"] = Value::String(input),
-            Message::UpdatePasswordPassword(input) => self.current_entry_edits["password"] = Value::String(input),
-            Message::UpdatePasswordOtpAuth(input) => self.current_entry_edits["otpauth"] = Value::String(input),
-            Message::UpdatePasswordFavorite(input) => self.current_entry_edits["favorite"] = Value::Bool(input),
-            Message::UpdatePasswordTags(input) => self.current_entry_edits["tags"] = Value::String(input),
-            Message::UpdatePasswordNotes(input) => self.current_entry_edits["notes"] = Value::String(input),
-
-            // Messages for updating identity entries
-            Message::UpdateIdentityTitle(input) => self.current_entry_edits["title"] = Value::String(input),
-            Message::UpdateIdentityFirstName(input) => self.current_entry_edits["first_name"] = Value::String(input),
-            Message::UpdateIdentityMiddleInitial(input) => self.current_entry_edits["middle_initial"] = Value::String(input),
-            Message::UpdateIdentityLastName(input) => self.current_entry_edits["last_name"] = Value::String(input),
-            Message::UpdateIdentityAddress(input) => self.current_entry_edits["address"] = Value::String(input),
-            Message::UpdateIdentityCity(input) => self.current_entry_edits["city"] = Value::String(input),
-            Message::UpdateIdentityCountry(input) => self.current_entry_edits["country"] = Value::String(input),
-            Message::UpdateIdentityState(input) => self.current_entry_edits["state"] = Value::String(input),
-            Message::UpdateIdentityZipcode(input) => self.current_entry_edits["zipcode"] = Value::String(input),
-            Message::UpdateIdentityPhone(input) => self.current_entry_edits["phone"] = Value::String(input),
-            Message::UpdateIdentityEmail(input) => self.current_entry_edits["email"] = Value::String(input),
-            Message::UpdateIdentityAptNumber(input) => self.current_entry_edits["apt_number"] = Value::String(input),
-
-            // Messages for updating card entries
-            Message::UpdateCardTitle(input) => self.current_entry_edits["title"] = Value::String(input),
-            Message::UpdateCardName(input) => self.current_entry_edits["name"] = Value::String(input),
-            Message::UpdateCardNumber(input) => self.current_entry_edits["card_number"] = Value::String(input),
-            Message::UpdateCardLastFour(input) => self.current_entry_edits["card_last_four"] = Value::String(input),
-            Message::UpdateCardExpirationDate(input) => self.current_entry_edits["expiration_date"] = Value::String(input),
-            Message::UpdateCardSecurityCode(input) => self.current_entry_edits["security_code"] = Value::String(input),
             _ => ()
         }
         Command::none()
     }
 
+    // Tick every second while a TOTP code is shown so the code and countdown stay current
+    fn subscription(&self) -> Subscription<Message> {
+        let is_totp = |uri: &str| matches!(OtpAuth::parse(uri), Ok(OtpAuth { kind: OtpKind::Totp { .. }, .. }));
+        let showing_totp = self.login_state == LoginState::LoggedIn
+            && self.current_entry_mode == PageMode::View
+            && (schema::fields(self.current_entry_type)
+                .iter()
+                .filter(|spec| matches!(spec.kind, FieldKind::Otp))
+                .any(|spec| is_totp(self.current_entry_edits[spec.key].as_str().unwrap_or("")))
+                || custom_fields(&self.current_entry_edits)
+                    .iter()
+                    .any(|field| field.field_type == CustomFieldType::Totp && is_totp(&totp_uri(&field.value))));
+
+        if showing_totp {
+            iced::time::every(Duration::from_secs(1)).map(|_| Message::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+
     fn view(&self) -> Element<Message> {
         // Add nav and window view together, display()
+        let all_tags = entry::all_tags(&self.entries);
+        let details_context = DetailsContext {
+            generator_options: &self.generator_options,
+            show_generator: self.show_entry_generator,
+            current_time: self.current_time,
+            tag_input: &self.tag_input,
+            known_tags: all_tags.keys().cloned().collect(),
+            folder_choices: folder::folder_choices(&self.entries),
+            revealed: self.revealed_entry.as_ref(),
+            ssh_path: &self.ssh_path,
+            ssh_passphrase: &self.ssh_passphrase,
+            ssh_status: &self.ssh_status,
+            attachment_path: &self.attachment_path,
+            attachment_status: &self.attachment_status,
+            transfer_path: &self.transfer_path,
+            transfer_status: &self.transfer_status,
+            expiry_warning_days: self.preferences.expiry_warning_days,
+            wifi_qr: self.revealed_entry.as_ref().and(self.revealed_qr.as_ref()),
+        };
+        let transfer_context = TransferContext {
+            path: &self.transfer_path,
+            status: &self.transfer_status,
+            import_format: self.import_format,
+            preview: self.import_preview.as_ref(),
+            skip_duplicates: self.skip_duplicates,
+            password: &self.transfer_password,
+            kdbx_cipher: self.kdbx_cipher,
+            gpg_command: &self.preferences.gpg_command,
+        };
+        let nav_context = NavContext {
+            tags: &all_tags,
+            selected_tags: &self.selected_tags,
+            show_tags: self.show_tags,
+            folders: folder::folder_tree(&self.entries)
+                .into_iter()
+                .map(|node| {
+                    let count = folder::entry_count(&self.entries, node.folder.id);
+                    (node, count)
+                })
+                .collect(),
+            selected_folder: self.selected_folder,
+            show_folders: self.show_folders,
+            folder_input: &self.folder_input,
+            folder_error: &self.folder_error,
+            pending_delete: self.selected_folder.filter(|_| self.confirm_folder_delete).map(|id| {
+                folder::folder_with_descendants(&self.entries, id).into_iter().map(|id| folder::entry_count(&self.entries, id)).sum()
+            }),
+        };
+        let filter = EntryFilter {
+            tags: &self.selected_tags,
+            folders: self.selected_folder.map(|id| folder::folder_with_descendants(&self.entries, id)),
+        };
+        let expiry = ExpiryWindow {
+            current_time: self.current_time,
+            warning_days: self.preferences.expiry_warning_days,
+        };
         let combine_views = |view| {
             Row::new()
-                .push(nav_page::view_page(self.current_style, self.current_page))
+                .push(nav_page::view_page(self.current_style, self.current_page, &nav_context))
                 .push(view)
-                .push(details_page::view_page(self.current_style, self.current_entry_mode, self.current_entry_type, &self.current_entry_edits))
+                .push(details_page::view_page(self.current_style, self.current_entry_mode, self.current_entry_type, &self.current_entry_edits, &details_context))
                 .into()
         };
 
@@ -189,16 +895,38 @@ impl Application for KeyboltApp {
                     .password();
                 
                 let submit_button =
-                    Button::new(Text::new("Unlock"))
+                    Button::new(Text::new(if self.vault_exists { "Unlock" } else { "Create" }))
                         .padding(8)
                         .on_press(Message::PasswordInputSubmit);
-            
-                let content = Row::new()
-                    .width(Length::Fixed(300.0))
+
+                let input_row = Row::new()
                     .spacing(5)
                     .push(input)
                     .push(submit_button);
-            
+
+                let mut content = Column::new()
+                    .width(Length::Fixed(300.0))
+                    .spacing(10)
+                    .push(Text::new(if self.vault_exists { "Unlock your vault" } else { "Create a new vault" }))
+                    .push(input_row);
+
+                // Creating a vault asks for the passphrase twice
+                if !self.vault_exists {
+                    content = content.push(
+                        TextInput::new("Confirm password...", &self.passphrase_confirm)
+                            .padding(8)
+                            .on_input(Message::PasswordConfirmInputChanged)
+                            .on_submit(Message::PasswordInputSubmit)
+                            .password()
+                    );
+                }
+                if !self.passphrase.is_empty() {
+                    content = content.push(generator_page::view_strength(&strength::estimate(&self.passphrase)));
+                }
+                if !self.login_error.is_empty() {
+                    content = content.push(Text::new(self.login_error.clone()));
+                }
+
                 Container::new(content)
                     .width(Length::Fill)
                     .height(Length::Fill)
@@ -209,10 +937,35 @@ impl Application for KeyboltApp {
                     )).into()
             },
             // User is logged in
-            (_, Pages::ProfilePage) => combine_views(profile_page::view_page(self.current_style)),
-            (_, Pages::PasswordsPage) => combine_views(passwords_page::view_page(self.current_style, &self.entries["passwords"], self.selected_entry_id)),
-            (_, Pages::IdentitiesPage) => combine_views(identities_page::view_page(self.current_style, &self.entries["identities"], self.selected_entry_id)),
-            (_, Pages::CardsPage) => combine_views(cards_page::view_page(self.current_style, &self.entries["cards"], self.selected_entry_id)),
+            (_, Pages::ProfilePage) => combine_views(profile_page::view_page(self.current_style, &self.entries, expiry, &transfer_context)),
+            (_, Pages::GeneratorPage) => combine_views(generator_page::view_page(self.current_style, &self.generator_options, &self.generated_password)),
+            (_, Pages::FavoritesPage) => combine_views(favorites_page::view_page(self.current_style, &self.entries, self.current_entry_type, self.selected_entry_id)),
+            (_, Pages::SearchPage) => combine_views(search_page::view_page(self.current_style, &self.entries, &self.search_query, self.current_entry_type, self.selected_entry_id)),
+            (_, Pages::PasswordsPage) => combine_views(passwords_page::view_page(self.current_style, &self.entries["passwords"], self.selected_entry_id, &self.search_query, self.preferences.sort_for(EntryType::Passwords), &filter)),
+            (_, Pages::IdentitiesPage) => combine_views(identities_page::view_page(self.current_style, &self.entries["identities"], self.selected_entry_id, &self.search_query, self.preferences.sort_for(EntryType::Identities), &filter, expiry)),
+            (_, Pages::NotesPage) => combine_views(notes_page::view_page(self.current_style, &self.entries["notes"], self.selected_entry_id, &self.search_query, self.preferences.sort_for(EntryType::Notes), &filter)),
+            (_, Pages::SshKeysPage) => combine_views(ssh_keys_page::view_page(self.current_style, &self.entries["ssh_keys"], self.selected_entry_id, &self.search_query, self.preferences.sort_for(EntryType::SshKeys), &filter)),
+            (_, Pages::ApiCredentialsPage) => combine_views(api_credentials_page::view_page(self.current_style, &self.entries["api_credentials"], self.selected_entry_id, &self.search_query, self.preferences.sort_for(EntryType::ApiCredentials), &filter, expiry)),
+            (_, Pages::BankAccountsPage) => combine_views(bank_accounts_page::view_page(self.current_style, &self.entries["bank_accounts"], self.selected_entry_id, &self.search_query, self.preferences.sort_for(EntryType::BankAccounts), &filter)),
+            (_, Pages::WifiNetworksPage) => combine_views(wifi_networks_page::view_page(self.current_style, &self.entries["wifi_networks"], self.selected_entry_id, &self.search_query, self.preferences.sort_for(EntryType::WifiNetworks), &filter)),
+            (_, Pages::CardsPage) => combine_views(cards_page::view_page(self.current_style, &self.entries["cards"], self.selected_entry_id, &self.search_query, self.preferences.sort_for(EntryType::Cards), &filter, expiry)),
+        }
+    }
+}
+
+impl KeyboltApp {
+    // Fill the entry being edited with a generated or imported key
+    fn apply_ssh_key(&mut self, result: Result<SshKeyMaterial, ssh::SshError>, success: &str) {
+        match result {
+            Ok(key) => {
+                self.current_entry_edits["private_key"] = Value::String(key.private_key);
+                self.current_entry_edits["public_key"] = Value::String(key.public_key);
+                self.current_entry_edits["fingerprint"] = Value::String(key.fingerprint);
+                self.current_entry_edits["comment"] = Value::String(key.comment);
+                self.ssh_path.clear();
+                self.ssh_status = String::from(success);
+            },
+            Err(e) => self.ssh_status = e.to_string(),
         }
     }
-} 
\ No newline at end of file
+}
@@ -3,47 +3,126 @@ use serde_json::Value;
 use crate::gui::pages::details_page::{PageMode, EntryType};
 use crate::gui::styles::types::style_type::StyleType;
 use crate::gui::core::app::Pages;
+use crate::import::{ImportFormat, ImportPreview};
+use crate::import::kdbx::KdbxCipher;
+use crate::secure::generator::GeneratorMode;
+use crate::vault::custom_field::CustomFieldType;
+use crate::vault::document::{DocumentField, DocumentType};
+use crate::vault::folder::FolderChoice;
+use crate::vault::sort::{Grouping, SortOrder};
 
 #[derive(Clone, Debug)]
 /// Messages types that permit to react to application interactions/subscriptions
 pub enum Message {
     ChangePage(Pages),
     ChangeStyle(StyleType),
+    ChangeExpiryWindow(i64),
     ChangeEntryMode(PageMode),
     SaveEntryEdits,
     SelectEntry(i32),
+    SelectEntryOfType(EntryType, i32),
+    // Any field listed in the entry type's schema, the type guards against stale updates
+    UpdateField { entry_type: EntryType, field: &'static str, value: Value },
+    ToggleFavorite(bool),
+    NewEntry,
+    ToggleRevealSecrets,
+    CopySecretField(&'static str),
+    CopyCustomField(usize),
+    CopyDocumentNumber(usize),
+    MoveToFolder(FolderChoice),
+    UpdateSearchQuery(String),
+    ChangeSortOrder(SortOrder),
+    ChangeGrouping(Grouping),
+
+    // Messages for tags
+    UpdateTagInput(String),
+    AddTag(String),
+    RemoveTag(String),
+    ToggleTagSection,
+    ToggleTagFilter(String),
+    // Folder messages
+    ToggleFolderSection,
+    SelectFolder(Option<u64>),
+    UpdateFolderInput(String),
+    CreateFolder,
+    RenameFolder,
+    // true also deletes the entries inside the folder, false moves them up to its parent
+    DeleteFolder(bool),
+    // Deleting with entries is asked for first, false cancels the question
+    ConfirmFolderDelete(bool),
     PasswordInputChanged(String),
+    PasswordConfirmInputChanged(String),
     PasswordInputSubmit,
+    Tick,
+    CopyToClipboard(String),
+    IncrementHotpCounter,
+
+    // Messages for custom fields, addressed by their position in the entry
+    AddCustomField,
+    UpdateCustomFieldLabel(usize, String),
+    UpdateCustomFieldType(usize, CustomFieldType),
+    UpdateCustomFieldValue(usize, String),
+    // true moves the field up, false down
+    MoveCustomField(usize, bool),
+    RemoveCustomField(usize),
+
+    // Identity documents, addressed by their position in the identity
+    AddDocument,
+    UpdateDocumentType(usize, DocumentType),
+    UpdateDocument(usize, DocumentField, String),
+    RemoveDocument(usize),
+
+    // Attachments, addressed by their position in the entry
+    UpdateAttachmentPath(String),
+    AddAttachment,
+    SaveAttachment(usize),
+    RemoveAttachment(usize),
+
+    // vCard and other vault file imports and exports
+    UpdateTransferPath(String),
+    ImportVcards,
+    // true exports every identity, false only the selected one
+    ExportVcards(bool),
+    // Other apps' exports are previewed before anything is added
+    ChangeImportFormat(ImportFormat),
+    PreviewImport,
+    // The file is read in the background, tagged with its request and format. Errors come back as their message.
+    ImportPreviewed(u64, ImportFormat, Result<ImportPreview, String>),
+    ToggleSkipDuplicates(bool),
+    ConfirmImport,
+    // The vault with the imported entries and the status to show
+    ImportApplied(Result<(Value, String), String>),
+    CancelImport,
+    // KeePass databases are opened and written with their own password
+    UpdateTransferPassword(String),
+    ChangeKdbxCipher(KdbxCipher),
+    ExportKdbx,
+    // Written in the background, with the status to show
+    KdbxExported(Result<String, String>),
+    UpdateGpgCommand(String),
+
+    // SSH key generation, import and export
+    UpdateSshKeyPath(String),
+    UpdateSshKeyPassphrase(String),
+    GenerateSshKey,
+    ImportSshKey,
+    ExportSshPublicKey,
 
-    // Messages for updating password entries
-    UpdatePasswordTitle(String),
-    UpdatePasswordUrl(String),
-    UpdatePasswordUsername(String),
-    UpdatePasswordPassword(String),
-    UpdatePasswordOtpAuth(String),
-    UpdatePasswordFavorite(bool),
-    UpdatePasswordTags(String),
-    UpdatePasswordNotes(String),
-
-    // Messages for updating identity entries
-    UpdateIdentityTitle(String),
-    UpdateIdentityFirstName(String),
-    UpdateIdentityMiddleInitial(String),
-    UpdateIdentityLastName(String),
-    UpdateIdentityAddress(String),
-    UpdateIdentityCity(String),
-    UpdateIdentityCountry(String),
-    UpdateIdentityState(String),
-    UpdateIdentityZipcode(String),
-    UpdateIdentityPhone(String),
-    UpdateIdentityEmail(String),
-    UpdateIdentityAptNumber(String),
-
-    // Messages for updating card entries
-    UpdateCardTitle(String),
-    UpdateCardName(String),
-    UpdateCardNumber(String),
-    UpdateCardLastFour(String),
-    UpdateCardExpirationDate(String),
-    UpdateCardSecurityCode(String),
+    // Messages for the password generator
+    ChangeGeneratorMode(GeneratorMode),
+    UpdateGeneratorLength(u8),
+    ToggleGeneratorLowercase(bool),
+    ToggleGeneratorUppercase(bool),
+    ToggleGeneratorDigits(bool),
+    ToggleGeneratorSymbols(bool),
+    ToggleGeneratorExcludeAmbiguous(bool),
+    ToggleGeneratorRequireEachClass(bool),
+    UpdateGeneratorWordCount(u8),
+    UpdateGeneratorSeparator(String),
+    ToggleGeneratorCapitalize(bool),
+    ToggleGeneratorIncludeNumber(bool),
+    RegeneratePassword,
+    CopyGeneratedPassword,
+    ToggleEntryGenerator(bool),
+    GenerateEntryPassword,
 }
\ No newline at end of file
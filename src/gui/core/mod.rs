@@ -1,2 +1,3 @@
 pub mod app;
-pub mod message;
\ No newline at end of file
+pub mod message;
+pub mod preferences;
\ No newline at end of file
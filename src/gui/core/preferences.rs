@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::vault::date::EXPIRY_WARNING_DAYS;
+use crate::vault::sort::SortPreference;
+
+// Location of the (unencrypted) user interface preferences
+const PREFERENCES_PATH: &str = "preferences.json";
+
+// Choices offered for how early expiring entries are flagged, in days
+pub const EXPIRY_WINDOW_CHOICES: [i64; 5] = [7, 14, 30, 60, 90];
+
+/// Interface preferences kept between sessions. Nothing sensitive is stored here.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Preferences {
+    // Sort order and grouping, keyed by entry type
+    #[serde(default)]
+    pub sort: HashMap<String, SortPreference>,
+    // Days before an expiry date that entries get flagged
+    #[serde(default = "default_expiry_warning_days")]
+    pub expiry_warning_days: i64,
+    // Command pass files are decrypted with when importing a password store
+    #[serde(default = "default_gpg_command")]
+    pub gpg_command: String,
+}
+
+impl Default for Preferences {
+    fn default() -> Preferences {
+        Preferences {
+            sort: HashMap::new(),
+            expiry_warning_days: default_expiry_warning_days(),
+            gpg_command: default_gpg_command(),
+        }
+    }
+}
+
+fn default_expiry_warning_days() -> i64 {
+    EXPIRY_WARNING_DAYS
+}
+
+fn default_gpg_command() -> String {
+    String::from("gpg")
+}
+
+impl Preferences {
+    // Load the saved preferences, falling back to the defaults if there are none
+    pub fn load() -> Preferences {
+        fs::read_to_string(PREFERENCES_PATH)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), io::Error> {
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(PREFERENCES_PATH, contents)
+    }
+
+    pub fn sort_for(&self, entry_type: EntryType) -> SortPreference {
+        self.sort.get(entry_type.as_str()).copied().unwrap_or_default()
+    }
+}
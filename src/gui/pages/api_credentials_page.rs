@@ -0,0 +1,81 @@
+use iced::Length;
+use iced::widget::{Column, Container, Text, Button, Scrollable, Row};
+use iced::Element;
+use serde_json::Value;
+
+// Import Message enum from the main application module
+use crate::gui::core::message::Message;
+use crate::gui::pages::details_page::EntryType;
+use crate::gui::pages::item_list;
+use crate::gui::styles::types::{
+    style_type::StyleType,
+    style_tuple::StyleTuple,
+    element_type::ElementType,
+};
+use crate::vault::expiry::{entry_expiry, ExpiryWindow};
+use crate::vault::entry::is_favorite;
+use crate::vault::search::{search_entries, filter_entries, field_text, EntryFilter};
+use crate::vault::sort::{arrange, SortPreference};
+
+// Define the user interface layout for the ApiCredentialsPage
+pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32, search_query: &str, sort: SortPreference, filter: &EntryFilter, expiry: ExpiryWindow) -> Element<'static, Message> {
+    // Create a text label for the ApiCredentialsPage
+    let label = Text::new("API credentials page");
+    let api_credential_entry = |entry_id: i32, label: Row<'static, Message>, service: Row<'static, Message>, expiry_warning: Option<String>| {
+        let mut content = Column::new()
+            .push(label)
+            .push(service);
+        if let Some(warning) = expiry_warning {
+            content = content.push(item_list::view_badge(style, warning));
+        }
+        Button::new(content)
+            .padding(25)
+            .width(Length::Fill)
+            .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                if entry_id == selected_entry_id {
+                    StyleTuple(style, ElementType::SelectedItem)
+                } else {
+                    StyleTuple(style, ElementType::ItemListEntry)
+                }
+            ))
+            .on_press(Message::SelectEntry(entry_id))
+    };
+
+    // Create a column layout, add the label, toolbar and matching entries to it
+    let mut col = Column::new()
+        .push(label)
+        .push(item_list::view_toolbar(search_query, sort));
+    let searching = !search_query.trim().is_empty();
+    let matches = filter_entries(entries, search_entries(entries, EntryType::ApiCredentials, search_query), filter);
+    if matches.is_empty() && searching {
+        col = col.push(Text::new("No matches"));
+    }
+    for (group, matches) in arrange(entries, matches, sort, searching) {
+        if let Some(header) = item_list::view_group_header(&group) {
+            col = col.push(header);
+        }
+        for found in matches {
+            let value = &entries[found.index];
+            col = col.push(
+                api_credential_entry(found.index as i32,
+                    item_list::with_favorite_marker(style, item_list::highlighted_text(style, field_text(&value["title"]), found.highlights.get("title")), is_favorite(value)),
+                    item_list::highlighted_text(style, field_text(&value["service"]), found.highlights.get("service")),
+                    entry_expiry(EntryType::ApiCredentials, value, expiry).and_then(|(_, status)| status.warning()),
+                ),
+            );
+        }
+    }
+
+    let scroll_area = Scrollable::new(col);
+
+    // Create a container to hold the column layout, set its dimensions and position, and return it as an Element
+    Container::new(scroll_area)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .style(<StyleTuple as Into<iced::theme::Container>>::into(
+            StyleTuple(style, ElementType::ItemListColumn),
+        ))
+        .into()
+}
\ No newline at end of file
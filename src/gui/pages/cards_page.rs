@@ -1,28 +1,41 @@
 use iced::Length;
-use iced::widget::{Column, Container, Text, Button, Scrollable};
+use iced::widget::{Column, Container, Text, Button, Scrollable, Row};
 use iced::Element;
 use serde_json::Value;
 
 // Import Message enum from the main application module
 use crate::gui::core::message::Message;
+use crate::gui::pages::details_page::EntryType;
+use crate::gui::pages::item_list;
 use crate::gui::styles::types::{
     style_type::StyleType,
     style_tuple::StyleTuple,
     element_type::ElementType,
 };
+use crate::vault::card::{detect_brand, CardBrand};
+use crate::vault::entry::is_favorite;
+use crate::vault::expiry::{entry_expiry, ExpiryWindow};
+use crate::vault::search::{search_entries, filter_entries, field_text, EntryFilter};
+use crate::vault::sort::{arrange, SortPreference};
 
 // Define the user interface layout for the CardsPage
-pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32) -> Element<'static, Message> {
+pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32, search_query: &str, sort: SortPreference, filter: &EntryFilter, expiry: ExpiryWindow) -> Element<'static, Message> {
     // Create a text label for the CardsPage
     let label = Text::new("Cards page");
 
-    let card_entry = |entry_id: i32, title: String, name: String, card_last_four: String| {
-        Button::new(
-            Column::new()
-                .push(Text::new(title))
-                .push(Text::new(name))
-                .push(Text::new(format!("****-****-****-{}", card_last_four)))
-        )
+    let card_entry = |entry_id: i32, title: Row<'static, Message>, name: Row<'static, Message>, card_last_four: String, brand: Option<CardBrand>, expiry_warning: Option<String>| {
+        let mut title = Row::new().spacing(5).push(title);
+        if let Some(brand) = brand {
+            title = title.push(item_list::view_badge(style, brand.to_string()));
+        }
+        let mut content = Column::new()
+            .push(title)
+            .push(name)
+            .push(Text::new(format!("****-****-****-{}", card_last_four)));
+        if let Some(warning) = expiry_warning {
+            content = content.push(item_list::view_badge(style, warning));
+        }
+        Button::new(content)
             .padding(25)
             .width(Length::Fill)
             .style(<StyleTuple as Into<iced::theme::Button>>::into(
@@ -35,23 +48,30 @@ pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32) -> E
             .on_press(Message::SelectEntry(entry_id))
     };
 
-    // Create a column layout, add the label and button to it
-    let mut col = Column::new().push(label);
-    let get_val = |value: &Value, label: &str| {
-        let s = serde_json::to_string(&value[label]).unwrap();
-        s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).map(|s| s.to_owned()).unwrap_or_else(|| s.to_owned())
-    };
-    let mut counter: i32 = 0;
-    if let Some(obj) = entries.as_array() {
-        for value in obj {
+    // Create a column layout, add the label, toolbar and matching entries to it
+    let mut col = Column::new()
+        .push(label)
+        .push(item_list::view_toolbar(search_query, sort));
+    let searching = !search_query.trim().is_empty();
+    let matches = filter_entries(entries, search_entries(entries, EntryType::Cards, search_query), filter);
+    if matches.is_empty() && searching {
+        col = col.push(Text::new("No matches"));
+    }
+    for (group, matches) in arrange(entries, matches, sort, searching) {
+        if let Some(header) = item_list::view_group_header(&group) {
+            col = col.push(header);
+        }
+        for found in matches {
+            let value = &entries[found.index];
             col = col.push(
-                card_entry(counter,
-                    get_val(value, "title"),
-                    get_val(value, "name"),
-                    get_val(value, "card_last_four"),
+                card_entry(found.index as i32,
+                    item_list::with_favorite_marker(style, item_list::highlighted_text(style, field_text(&value["title"]), found.highlights.get("title")), is_favorite(value)),
+                    item_list::highlighted_text(style, field_text(&value["name"]), found.highlights.get("name")),
+                    field_text(&value["card_last_four"]),
+                    detect_brand(value["card_number"].as_str().unwrap_or("")),
+                    entry_expiry(EntryType::Cards, value, expiry).and_then(|(_, status)| status.warning()),
                 ),
             );
-            counter += 1;
         }
     }
 
@@ -1,7 +1,21 @@
-use iced::{widget::{Container, Column, Text, Space, Button, Row, Scrollable, TextInput}, Element, Length};
+use iced::{widget::{Container, Column, Text, Space, Button, Row, Scrollable, TextInput, Checkbox, ProgressBar, PickList, qr_code, QRCode}, Element, Length};
 use serde_json::Value;
 
 use crate::gui::{styles::types::{element_type::ElementType, style_tuple::StyleTuple, style_type::StyleType}, core::{message::Message}};
+use crate::gui::pages::{generator_page, item_list};
+use crate::secure::attachment;
+use crate::secure::generator::GeneratorOptions;
+use crate::secure::strength;
+use crate::secure::totp::{self, OtpAuth, OtpKind};
+use crate::vault::custom_field::{custom_fields, totp_uri, validation_error, CustomFieldType};
+use crate::vault::date::expiry_status;
+use crate::vault::entry::{entry_tags, field_lines, is_favorite};
+use crate::vault::document::{documents, validation_error as document_validation_error, DocumentField, DocumentType};
+use crate::vault::folder::{entry_folder, FolderChoice};
+use crate::vault::identity::format_address;
+use crate::vault::schema::{self, FieldChoice, FieldKind, FieldSpec};
+use crate::vault::search::field_text;
+use crate::vault::wifi::{wifi_payload, WifiSecurity};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PageMode {
@@ -10,25 +24,105 @@ pub enum PageMode {
     Closed,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum EntryType {
     Passwords,
     Identities,
     Cards,
+    Notes,
+    SshKeys,
+    ApiCredentials,
+    BankAccounts,
+    WifiNetworks,
 }
 
 impl EntryType {
+    pub const ALL: [EntryType; 8] = [
+        EntryType::Passwords,
+        EntryType::Identities,
+        EntryType::Cards,
+        EntryType::Notes,
+        EntryType::SshKeys,
+        EntryType::ApiCredentials,
+        EntryType::BankAccounts,
+        EntryType::WifiNetworks,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
             EntryType::Passwords => "passwords",
             EntryType::Identities => "identities",
             EntryType::Cards => "cards",
+            EntryType::Notes => "notes",
+            EntryType::SshKeys => "ssh_keys",
+            EntryType::ApiCredentials => "api_credentials",
+            EntryType::BankAccounts => "bank_accounts",
+            EntryType::WifiNetworks => "wifi_networks",
+        }
+    }
+
+    // Singular name shown next to entries in mixed lists
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryType::Passwords => "Password",
+            EntryType::Identities => "Identity",
+            EntryType::Cards => "Card",
+            EntryType::Notes => "Secure Note",
+            EntryType::SshKeys => "SSH Key",
+            EntryType::ApiCredentials => "API Credential",
+            EntryType::BankAccounts => "Bank Account",
+            EntryType::WifiNetworks => "Wi-Fi Network",
+        }
+    }
+
+    // Field shown below the title in mixed lists, the most identifying one of each type
+    pub fn subtitle_field(&self) -> &'static str {
+        match self {
+            EntryType::Passwords => "username",
+            EntryType::Identities => "email",
+            EntryType::Cards => "name",
+            EntryType::Notes => "tags",
+            EntryType::SshKeys => "fingerprint",
+            EntryType::ApiCredentials => "service",
+            EntryType::BankAccounts => "bank_name",
+            EntryType::WifiNetworks => "ssid",
         }
     }
 }
 
+/// Editor state shown alongside the entry's own fields
+pub struct DetailsContext<'a> {
+    pub generator_options: &'a GeneratorOptions,
+    pub show_generator: bool,
+    pub current_time: u64,
+    pub tag_input: &'a str,
+    /// Every tag used in the vault, offered as autocomplete suggestions
+    pub known_tags: Vec<String>,
+    /// Destinations for "Move to folder"
+    pub folder_choices: Vec<FolderChoice>,
+    /// Decrypted copy of the entry while its secrets are revealed in view mode
+    pub revealed: Option<&'a Value>,
+    /// Private key file (or pasted key) to import and public key file to export to
+    pub ssh_path: &'a str,
+    pub ssh_passphrase: &'a str,
+    /// Outcome of the last SSH key generation, import or export
+    pub ssh_status: &'a str,
+    /// File to attach, or where to save an attachment to
+    pub attachment_path: &'a str,
+    /// Outcome of the last attachment change or download
+    pub attachment_status: &'a str,
+    /// File to import from or export to, e.g. a .vcf file
+    pub transfer_path: &'a str,
+    /// Outcome of the last import or export
+    pub transfer_status: &'a str,
+    /// Days before an expiry date that it gets flagged
+    pub expiry_warning_days: i64,
+    /// QR code of the Wi-Fi sharing payload, only built while the passphrase is revealed
+    pub wifi_qr: Option<&'a qr_code::State>,
+}
+
 // Define the user interface layout for the ProfilePage
-pub fn view_page(style: StyleType, current_page_mode: PageMode, entry_type: EntryType, entry_data_edits: &Value) -> Element<'static, Message> {
+pub fn view_page<'a>(style: StyleType, current_page_mode: PageMode, entry_type: EntryType, entry_data_edits: &Value, context: &DetailsContext<'a>) -> Element<'a, Message> {
     match current_page_mode {
         PageMode::Closed => {
             Space::new(Length::Fixed(0.0), Length::Fixed(0.0)).into()
@@ -64,319 +158,17 @@ pub fn view_page(style: StyleType, current_page_mode: PageMode, entry_type: Entr
                 .push(keybolt_title)
                 .push(edit_toggle_btn);
 
-            // BODY
-            let details_body =
-                match current_page_mode {
-                    PageMode::View => {
-                        match entry_type {
-                            EntryType::Passwords => {
-                                let title_label = Text::new("Title: ").size(16);
-                                let title_value = Text::new(entry_data_edits["title"].to_string()).size(16);
-
-                                let url_label = Text::new("URL: ").size(16);
-                                let url_value = Text::new(entry_data_edits["url"].to_string()).size(16);
-
-                                let username_label = Text::new("Username: ").size(16);
-                                let username_value = Text::new(entry_data_edits["username"].to_string()).size(16);
-
-                                let password_label = Text::new("Password: ").size(16);
-                                let password_value = Text::new(entry_data_edits["password"].to_string()).size(16);
-
-                                let otpauth_label = Text::new("OTP Auth: ").size(16);
-                                let otpauth_value = Text::new(entry_data_edits["otpauth"].to_string()).size(16);
-
-                                // let favorite_label = Text::new("Favorite: ").size(16);
-                                // let favorite_value = Text::new(if entry_data_edits.favorite { "Yes" } else { "No" }).size(16);
-
-                                let tags_label = Text::new("Tags: ").size(16);
-                                let tags_value = Text::new(entry_data_edits["tags"].to_string());
-
-                                let notes_label = Text::new("Notes: ").size(16);
-                                let notes_value = Text::new(entry_data_edits["notes"].to_string()).size(16);
-
-                                let content = Column::new()
-                                    .spacing(10)
-                                    .push(title_label)
-                                    .push(title_value)
-                                    .push(url_label)
-                                    .push(url_value)
-                                    .push(username_label)
-                                    .push(username_value)
-                                    .push(password_label)
-                                    .push(password_value)
-                                    .push(otpauth_label)
-                                    .push(otpauth_value)
-                                    // .push(favorite_label)
-                                    // .push(favorite_value)
-                                    .push(tags_label)
-                                    .push(tags_value)
-                                    .push(notes_label)
-                                    .push(notes_value)
-                                    .width(iced::Length::Fill);
-
-                                Scrollable::new(content)
-                            },
-                            EntryType::Identities => {
-                                let title_label = Text::new("Title: ").size(16);
-                                let title_value = Text::new(entry_data_edits["title"].to_string()).size(16);
-
-                                let first_name_label = Text::new("First Name: ").size(16);
-                                let first_name_value = Text::new(entry_data_edits["first_name"].to_string()).size(16);
-
-                                let middle_initial_label = Text::new("Middle Initial: ").size(16);
-                                let middle_initial_value = Text::new(entry_data_edits["middle_initial"].to_string()).size(16);
-
-                                let last_name_label = Text::new("Last Name: ").size(16);
-                                let last_name_value = Text::new(entry_data_edits["last_name"].to_string()).size(16);
-
-                                let address_label = Text::new("Address: ").size(16);
-                                let address_value = Text::new(entry_data_edits["address"].to_string()).size(16);
-
-                                let city_label = Text::new("City: ").size(16);
-                                let city_value = Text::new(entry_data_edits["city"].to_string()).size(16);
-
-                                let country_label = Text::new("Country: ").size(16);
-                                let country_value = Text::new(entry_data_edits["country"].to_string()).size(16);
-
-                                let state_label = Text::new("State: ").size(16);
-                                let state_value = Text::new(entry_data_edits["state"].to_string()).size(16);
-
-                                let zipcode_label = Text::new("Zipcode: ").size(16);
-                                let zipcode_value = Text::new(entry_data_edits["zipcode"].to_string()).size(16);
-
-                                let phone_label = Text::new("Phone: ").size(16);
-                                let phone_value = Text::new(entry_data_edits["phone"].to_string()).size(16);
-
-                                let email_label = Text::new("Email: ").size(16);
-                                let email_value = Text::new(entry_data_edits["email"].to_string()).size(16);
-
-                                let apt_number_label = Text::new("Apt Number: ").size(16);
-                                let apt_number_value = Text::new(entry_data_edits["apt_number"].to_string()).size(16);
-
-                                let content = Column::new()
-                                    .spacing(10)
-                                    .push(title_label)
-                                    .push(title_value)
-                                    .push(first_name_label)
-                                    .push(first_name_value)
-                                    .push(middle_initial_label)
-                                    .push(middle_initial_value)
-                                    .push(last_name_label)
-                                    .push(last_name_value)
-                                    .push(address_label)
-                                    .push(address_value)
-                                    .push(city_label)
-                                    .push(city_value)
-                                    .push(country_label)
-                                    .push(country_value)
-                                    .push(state_label)
-                                    .push(state_value)
-                                    .push(zipcode_label)
-                                    .push(zipcode_value)
-                                    .push(phone_label)
-                                    .push(phone_value)
-                                    .push(email_label)
-                                    .push(email_value)
-                                    .push(apt_number_label)
-                                    .push(apt_number_value)
-                                    .width(iced::Length::Fill);
-
-                                Scrollable::new(content)
-                            },
-                            EntryType::Cards => {
-                                let title_label = Text::new("Title: ").size(16);
-                                let title_value = Text::new(entry_data_edits["title"].to_string()).size(16);
-
-                                let card_number_label = Text::new("Card Number: ").size(16);
-                                let card_number_value = Text::new(entry_data_edits["card_number"].to_string()).size(16);
-
-                                let cardholder_name_label = Text::new("Cardholder Name: ").size(16);
-                                let cardholder_name_value = Text::new(entry_data_edits["cardholder_name"].to_string()).size(16);
-
-                                let expiration_date_label = Text::new("Expiration Date: ").size(16);
-                                let expiration_date_value = Text::new(entry_data_edits["expiration_date"].to_string()).size(16);
-
-                                let security_code_label = Text::new("Security Code: ").size(16);
-                                let security_code_value = Text::new(entry_data_edits["security_code"].to_string()).size(16);
-
-                                let content = Column::new()
-                                    .spacing(10)
-                                    .push(title_label)
-                                    .push(title_value)
-                                    .push(card_number_label)
-                                    .push(card_number_value)
-                                    .push(cardholder_name_label)
-                                    .push(cardholder_name_value)
-                                    .push(expiration_date_label)
-                                    .push(expiration_date_value)
-                                    .push(security_code_label)
-                                    .push(security_code_value)
-                                    .width(iced::Length::Fill);
-
-                                Scrollable::new(content)
-                            }
-                        }
-                    },
-                    PageMode::Edit | _ => {
-                        match entry_type {
-                            EntryType::Passwords => {
-                                let title = TextInput::new("Title", &entry_data_edits["title"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdatePasswordTitle);
-                        
-                                let url = TextInput::new("URL", &entry_data_edits["url"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdatePasswordUrl);
-                        
-                                let username = TextInput::new("Username", &entry_data_edits["username"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdatePasswordUsername);
-                        
-                                let password = TextInput::new("Password", &entry_data_edits["password"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdatePasswordPassword);
-                        
-                                let otpauth = TextInput::new("OTPAuth", &entry_data_edits["otpauth"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdatePasswordOtpAuth);
-                        
-                                //TODO bool
-                                // let favorite = TextInput::new("Favorite", &entry_data["favorite"].as_str().unwrap_or(""))
-                                //     .padding(8)
-                                //     .on_input(Message::UpdatePasswordFavorite);
-                        
-                                let tags = TextInput::new("Tags", &entry_data_edits["tags"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdatePasswordTags);
-                        
-                                let notes = TextInput::new("Notes", &entry_data_edits["notes"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdatePasswordNotes);
-                        
-                                Scrollable::new(
-                                    Column::new()
-                                        .push(title)
-                                        .push(url)
-                                        .push(username)
-                                        .push(password)
-                                        .push(otpauth)
-                                        //TODO bool
-                                        // .push(favorite)
-                                        .push(tags)
-                                        .push(notes)
-                                        .width(iced::Length::Fill)
-                                )
-                            },
-                            EntryType::Identities => {
-                                let title = TextInput::new("Title", &entry_data_edits["title"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityTitle);
-                        
-                                let first_name = TextInput::new("First Name", &entry_data_edits["first_name"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityFirstName);
-                        
-                                let middle_initial = TextInput::new("Middle Initial", &entry_data_edits["middle_initial"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityMiddleInitial);
-                        
-                                let last_name = TextInput::new("Last Name", &entry_data_edits["last_name"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityLastName);
-                        
-                                let address = TextInput::new("Address", &entry_data_edits["address"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityAddress);
-                        
-                                let city = TextInput::new("City", &entry_data_edits["city"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityCity);
-                        
-                                let country = TextInput::new("Country", &entry_data_edits["country"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityCountry);
-                        
-                                let state = TextInput::new("State", &entry_data_edits["state"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityState);
-                        
-                                let zipcode = TextInput::new("Zipcode", &entry_data_edits["zipcode"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityZipcode);
-                        
-                                let phone = TextInput::new("Phone", &entry_data_edits["phone"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityPhone);
-                        
-                                let email = TextInput::new("Email", &entry_data_edits["email"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityEmail);
-                        
-                                let apt_number = TextInput::new("Apt Number", &entry_data_edits["apt_number"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateIdentityAptNumber);
-                        
-                                Scrollable::new(
-                                    Column::new()
-                                        .push(title)
-                                        .push(first_name)
-                                        .push(middle_initial)
-                                        .push(last_name)
-                                        .push(address)
-                                        .push(city)
-                                        .push(country)
-                                        .push(state)
-                                        .push(zipcode)
-                                        .push(phone)
-                                        .push(email)
-                                        .push(apt_number)
-                                        .width(iced::Length::Fill)
-                                    )
-                            },
-                            EntryType::Cards => {
-                                let title = TextInput::new("Title", &entry_data_edits["title"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateCardTitle);
-                        
-                                let name = TextInput::new("Name", &entry_data_edits["name"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateCardName);
-                        
-                                let card_number = TextInput::new("Card Number", &entry_data_edits["card_number"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateCardNumber);
-                        
-                                let card_last_four = TextInput::new("Card Last Four", &entry_data_edits["card_last_four"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateCardLastFour);
-                        
-                                let expiration_date = TextInput::new("Expiration Date", &entry_data_edits["expiration_date"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateCardExpirationDate);
-                        
-                                let security_code = TextInput::new("Security Code", &entry_data_edits["security_code"].as_str().unwrap_or(""))
-                                    .padding(8)
-                                    .on_input(Message::UpdateCardSecurityCode);
-                        
-                                Scrollable::new(
-                                    Column::new()
-                                        .push(title)
-                                        .push(name)
-                                        .push(card_number)
-                                        .push(card_last_four)
-                                        .push(expiration_date)
-                                        .push(security_code)
-                                        .width(iced::Length::Fill)
-                                )
-                            }
-                        }
-                    }
-                };
+            // BODY, laid out from the entry type's field schema
+            let details_body = match current_page_mode {
+                PageMode::View => Scrollable::new(view_fields(style, entry_type, entry_data_edits, context)),
+                PageMode::Edit | _ => Scrollable::new(view_field_editors(style, entry_type, entry_data_edits, context)),
+            };
 
             // Create nav container
             Container::new(
                 Column::new()
                     .push(header_row)
+                    .push(view_folder_menu(entry_data_edits, &context.folder_choices))
                     .push(details_body)
                 )
                 .width(iced::Length::Fixed(300.0))
@@ -386,4 +178,616 @@ pub fn view_page(style: StyleType, current_page_mode: PageMode, entry_type: Entr
                 )).into()
         },
     }
-}
\ No newline at end of file
+}
+
+// Every field of the entry in view mode, followed by the fields common to all types
+fn view_fields<'a>(style: StyleType, entry_type: EntryType, entry_data_edits: &Value, context: &DetailsContext<'a>) -> Column<'a, Message> {
+    let mut content = Column::new().spacing(10);
+    for spec in schema::fields(entry_type).iter().filter(|spec| spec.is_shown(entry_data_edits)) {
+        content = content
+            .push(Text::new(format!("{}: ", spec.label_for(entry_data_edits))).size(16))
+            .push(view_field(style, spec, entry_data_edits, context));
+    }
+
+    content
+        .push(view_type_actions(style, entry_type, entry_data_edits, context))
+        .push(Checkbox::new("Favorite", is_favorite(entry_data_edits), Message::ToggleFavorite))
+        .push(Text::new("Tags: ").size(16))
+        .push(view_tags(style, entry_data_edits))
+        .push(view_custom_fields(style, entry_data_edits, context))
+        .push(view_attachments(entry_data_edits, context, false))
+        .width(iced::Length::Fill)
+}
+
+fn view_field(style: StyleType, spec: &'static FieldSpec, entry_data_edits: &Value, context: &DetailsContext) -> Element<'static, Message> {
+    let value = field_text(&entry_data_edits[spec.key]);
+    match spec.kind {
+        kind if kind.is_secret() => view_secret(spec, context),
+        FieldKind::Otp => view_otp(style, &value, context.current_time).into(),
+        FieldKind::Flag => Text::new(if entry_data_edits[spec.key].as_bool().unwrap_or(false) { "Yes" } else { "No" }).size(16).into(),
+        FieldKind::Choice(choices) => {
+            let choice = choices().into_iter().find(|choice| choice.value.eq_ignore_ascii_case(&value));
+            Text::new(choice.map_or(value, |choice| choice.label)).size(16).into()
+        },
+        FieldKind::Expiry | FieldKind::CardExpiry => {
+            let mut row = Row::new()
+                .spacing(5)
+                .push(Text::new(if value.is_empty() { String::from("Never") } else { value.clone() }).size(16));
+            if let Some(error) = spec.error(&value, entry_data_edits) {
+                row = row.push(item_list::view_badge(style, error));
+            }
+            let status = spec.expires_on(&value).and_then(|date| expiry_status(&date, context.current_time, context.expiry_warning_days));
+            if let Some(warning) = status.and_then(|status| status.warning()) {
+                row = row.push(item_list::view_badge(style, warning));
+            }
+            row.into()
+        },
+        _ => {
+            // Long derived values like public keys get a smaller font
+            let size = if spec.kind.is_derived() { 14 } else { 16 };
+            let mut row = Row::new().spacing(5).push(Text::new(spec.display(&value)).size(size));
+            if spec.copy && !value.is_empty() {
+                row = row.push(Button::new("Copy").on_press(Message::CopyToClipboard(value.clone())));
+            }
+            if let Some(error) = spec.error(&value, entry_data_edits) {
+                row = row.push(item_list::view_badge(style, error));
+            }
+            row.into()
+        },
+    }
+}
+
+// Secrets stay encrypted until they're revealed, but can be copied either way
+fn view_secret(spec: &'static FieldSpec, context: &DetailsContext) -> Element<'static, Message> {
+    let buttons = Row::new()
+        .spacing(5)
+        .push(Button::new(if context.revealed.is_some() { "Hide" } else { "Show" }).on_press(Message::ToggleRevealSecrets))
+        .push(Button::new("Copy").on_press(Message::CopySecretField(spec.key)));
+    let (text, size) = match context.revealed {
+        Some(revealed) => (field_text(&revealed[spec.key]), if spec.kind.is_derived() { 12 } else { 16 }),
+        None => (String::from("Hidden"), 16),
+    };
+    match spec.kind {
+        // Multi-line secrets get their buttons below them
+        FieldKind::SecretLines | FieldKind::DerivedSecret => Column::new()
+            .spacing(5)
+            .push(Text::new(text).size(size))
+            .push(buttons)
+            .into(),
+        _ => Row::new()
+            .spacing(5)
+            .push(Text::new(text).size(size))
+            .push(buttons)
+            .into(),
+    }
+}
+
+// Actions that only make sense for one entry type, shown after its fields
+fn view_type_actions<'a>(style: StyleType, entry_type: EntryType, entry_data_edits: &Value, context: &DetailsContext<'a>) -> Column<'a, Message> {
+    match entry_type {
+        // Public keys can be saved to a file, e.g. to hand over as an authorized key
+        EntryType::SshKeys => Column::new()
+            .spacing(5)
+            .push(Row::new()
+                .spacing(5)
+                .push(TextInput::new("~/.ssh/id_ed25519.pub", context.ssh_path)
+                    .padding(8)
+                    .on_input(Message::UpdateSshKeyPath))
+                .push(Button::new("Export").padding(8).on_press(Message::ExportSshPublicKey)))
+            .push(Text::new(context.ssh_status.to_string()).size(14)),
+        // The address as it would be written on an envelope in the identity's country
+        EntryType::Identities => {
+            let address = format_address(entry_data_edits);
+            let mut preview = Column::new().spacing(5).push(Text::new("Mailing Address: ").size(16));
+            if address.is_empty() {
+                preview = preview.push(Text::new("No address").size(14));
+            } else {
+                preview = preview
+                    .push(Text::new(address.clone()).size(16))
+                    .push(Button::new("Copy").on_press(Message::CopyToClipboard(address)));
+            }
+            // Single identities are shared as a vCard, e.g. to import into a contacts app
+            let export = Column::new()
+                .spacing(5)
+                .push(Row::new()
+                    .spacing(5)
+                    .push(TextInput::new("~/contact.vcf", context.transfer_path)
+                        .padding(8)
+                        .on_input(Message::UpdateTransferPath))
+                    .push(Button::new("Export vCard").padding(8).on_press(Message::ExportVcards(false))))
+                .push(Text::new(context.transfer_status.to_string()).size(14));
+            Column::new()
+                .spacing(10)
+                .push(preview)
+                .push(export)
+                .push(view_documents(style, entry_data_edits, context))
+        },
+        // Sharing needs the passphrase, so the QR code only exists while it's revealed
+        EntryType::WifiNetworks => {
+            let share = match (context.revealed, context.wifi_qr) {
+                (Some(revealed), Some(qr)) => {
+                    let payload = wifi_payload(
+                        revealed["ssid"].as_str().unwrap_or(""),
+                        WifiSecurity::from_stored(revealed["security"].as_str().unwrap_or("")),
+                        revealed["passphrase"].as_str().unwrap_or(""),
+                        entry_data_edits["hidden"].as_bool().unwrap_or(false),
+                    );
+                    Column::new()
+                        .spacing(5)
+                        .push(QRCode::new(qr).cell_size(5))
+                        .push(Button::new("Copy WIFI: payload").on_press(Message::CopyToClipboard(payload)))
+                },
+                _ => Column::new().push(Text::new("Show the passphrase to get a QR code").size(14)),
+            };
+            Column::new()
+                .spacing(10)
+                .push(Text::new("Share: ").size(16))
+                .push(share)
+        },
+        _ => Column::new(),
+    }
+}
+
+// Inputs for every editable field of the entry, followed by the fields common to all types
+fn view_field_editors(style: StyleType, entry_type: EntryType, entry_data_edits: &Value, context: &DetailsContext) -> Column<'static, Message> {
+    let mut content = Column::new();
+    for spec in schema::fields(entry_type).iter().filter(|spec| spec.is_shown(entry_data_edits)) {
+        content = content.push(view_field_editor(style, entry_type, spec, entry_data_edits, context));
+    }
+
+    content
+        .push(view_type_editor_actions(style, entry_type, entry_data_edits, context))
+        .push(Checkbox::new("Favorite", is_favorite(entry_data_edits), Message::ToggleFavorite))
+        .push(view_tag_editor(style, entry_data_edits, context))
+        .push(view_custom_field_editor(entry_data_edits))
+        .push(view_attachments(entry_data_edits, context, true))
+        .width(iced::Length::Fill)
+}
+
+fn view_field_editor(style: StyleType, entry_type: EntryType, spec: &'static FieldSpec, entry_data_edits: &Value, context: &DetailsContext) -> Element<'static, Message> {
+    let update = move |value: Value| Message::UpdateField { entry_type, field: spec.key, value };
+    let value = field_text(&entry_data_edits[spec.key]);
+    let label = spec.label_for(entry_data_edits);
+    match spec.kind {
+        FieldKind::Flag => Checkbox::new(label, entry_data_edits[spec.key].as_bool().unwrap_or(false), move |checked| update(Value::Bool(checked))).into(),
+        FieldKind::Choice(choices) => {
+            let choices = choices();
+            let selected = choices.iter().find(|choice| choice.value.eq_ignore_ascii_case(&value)).or(choices.first()).cloned();
+            Row::new()
+                .spacing(5)
+                .padding(5)
+                .push(Text::new(format!("{}: ", label)).size(16))
+                .push(PickList::new(choices, selected, move |choice: FieldChoice| update(Value::from(choice.value))))
+                .into()
+        },
+        // Text inputs are single line, so the body is edited one line per input.
+        // Enter starts a new line below the current one.
+        FieldKind::SecretLines => {
+            let lines = field_lines(entry_data_edits, spec.key);
+            let mut body = Column::new().spacing(2);
+            for (i, line) in lines.iter().enumerate() {
+                let (edited, inserted, removed) = (lines.clone(), lines.clone(), lines.clone());
+                body = body.push(Row::new()
+                    .push(TextInput::new(label, line)
+                        .padding(8)
+                        .on_input(move |input| {
+                            let mut lines = edited.clone();
+                            lines[i] = input;
+                            update(Value::String(lines.join("\n")))
+                        })
+                        .on_submit({
+                            let mut lines = inserted;
+                            lines.insert(i + 1, String::new());
+                            update(Value::String(lines.join("\n")))
+                        }))
+                    .push(Button::new("x")
+                        .padding(8)
+                        .on_press({
+                            let mut lines = removed;
+                            lines.remove(i);
+                            update(Value::String(lines.join("\n")))
+                        })));
+            }
+            body.into()
+        },
+        // Derived fields are replaced as a whole, e.g. by generating a new key
+        FieldKind::Derived => Text::new(format!("{}: {}", label, value)).size(14).into(),
+        FieldKind::DerivedSecret => Space::new(Length::Fixed(0.0), Length::Fixed(0.0)).into(),
+        // Generator options are hidden behind a checkbox to keep the form short
+        FieldKind::Password => {
+            let generate_btn = Button::new("Generate")
+                .padding(8)
+                .on_press(Message::GenerateEntryPassword)
+                .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                    StyleTuple(style, ElementType::Button),
+                ));
+            let mut password = Column::new()
+                .push(Row::new()
+                    .push(TextInput::new(label, &value)
+                        .padding(8)
+                        .on_input(move |input| update(Value::String(input))))
+                    .push(generate_btn))
+                .push(generator_page::view_strength(&strength::estimate(&value)))
+                .push(Checkbox::new("Generator options", context.show_generator, Message::ToggleEntryGenerator));
+            if context.show_generator {
+                password = password.push(generator_page::view_options(context.generator_options));
+            }
+            password.into()
+        },
+        _ => {
+            let placeholder = match spec.hint {
+                Some(hint) => format!("{} ({})", label, hint),
+                None => label.to_string(),
+            };
+            // Validation errors show right below the input, empty fields aren't flagged
+            let mut col = Column::new().push(TextInput::new(&placeholder, &value)
+                .padding(8)
+                .on_input(move |input| update(Value::String(input))));
+            if let Some(error) = spec.error(&value, entry_data_edits) {
+                col = col.push(Text::new(error).size(14));
+            }
+            col.into()
+        },
+    }
+}
+
+// Editing actions that only make sense for one entry type
+fn view_type_editor_actions(style: StyleType, entry_type: EntryType, entry_data_edits: &Value, context: &DetailsContext) -> Column<'static, Message> {
+    match entry_type {
+        EntryType::Identities => view_document_editor(entry_data_edits),
+        // Existing keys are imported from a file or pasted in
+        EntryType::SshKeys => Column::new()
+            .spacing(5)
+            .push(Button::new("Generate Ed25519 key")
+                .padding(8)
+                .on_press(Message::GenerateSshKey)
+                .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                    StyleTuple(style, ElementType::Button),
+                )))
+            .push(Text::new("Import an OpenSSH private key").size(16))
+            .push(TextInput::new("Key file path or pasted key", context.ssh_path)
+                .padding(8)
+                .on_input(Message::UpdateSshKeyPath))
+            .push(Row::new()
+                .spacing(5)
+                .push(TextInput::new("Key passphrase, if any", context.ssh_passphrase)
+                    .padding(8)
+                    .on_input(Message::UpdateSshKeyPassphrase)
+                    .on_submit(Message::ImportSshKey)
+                    .password())
+                .push(Button::new("Import").padding(8).on_press(Message::ImportSshKey)))
+            .push(Text::new(context.ssh_status.to_string()).size(14)),
+        _ => Column::new(),
+    }
+}
+
+// Identity documents in view mode, numbers stay encrypted until the entry is revealed
+fn view_documents(style: StyleType, entry_data_edits: &Value, context: &DetailsContext) -> Column<'static, Message> {
+    let revealed_documents = context.revealed.map(documents);
+    let mut col = Column::new().spacing(5).push(Text::new("Documents: ").size(16));
+    let entry_documents = documents(entry_data_edits);
+    if entry_documents.is_empty() {
+        col = col.push(Text::new("No documents").size(14));
+    }
+    for (i, document) in entry_documents.into_iter().enumerate() {
+        let number = revealed_documents.as_ref().and_then(|documents| documents.get(i)).map(|document| document.number.clone());
+        let mut title = Row::new().spacing(5).push(Text::new(document.document_type.label()).size(16));
+        let status = document.expires_on().and_then(|date| expiry_status(&date, context.current_time, context.expiry_warning_days));
+        if let Some(warning) = status.and_then(|status| status.warning()) {
+            title = title.push(item_list::view_badge(style, warning));
+        }
+        if let Some(error) = document_validation_error(&document) {
+            title = title.push(item_list::view_badge(style, error.to_string()));
+        }
+        let issuer = [document.issuing_country_name(), document.issuing_authority.trim().to_string()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let dates = match (document.issued.trim(), document.expires.trim()) {
+            ("", "") => String::new(),
+            (issued, "") => format!("Issued {}", issued),
+            ("", expires) => format!("Expires {}", expires),
+            (issued, expires) => format!("Issued {}, expires {}", issued, expires),
+        };
+        col = col
+            .push(title)
+            .push(Row::new()
+                .spacing(5)
+                .push(Text::new(number.unwrap_or_else(|| String::from("Hidden"))).size(16))
+                .push(Button::new(if context.revealed.is_some() { "Hide" } else { "Show" }).on_press(Message::ToggleRevealSecrets))
+                .push(Button::new("Copy").on_press(Message::CopyDocumentNumber(i))));
+        if !issuer.is_empty() {
+            col = col.push(Text::new(format!("Issued by {}", issuer)).size(14));
+        }
+        if !dates.is_empty() {
+            col = col.push(Text::new(dates).size(14));
+        }
+    }
+    col
+}
+
+// Editable identity documents, one block of inputs per document
+fn view_document_editor(entry_data_edits: &Value) -> Column<'static, Message> {
+    let mut col = Column::new()
+        .spacing(5)
+        .padding(5)
+        .push(Text::new("Documents").size(16));
+    for (i, document) in documents(entry_data_edits).into_iter().enumerate() {
+        let input = move |placeholder: &str, value: &str, field: DocumentField| TextInput::new(placeholder, value)
+            .padding(8)
+            .on_input(move |input| Message::UpdateDocument(i, field, input));
+        col = col
+            .push(Row::new()
+                .spacing(5)
+                .push(PickList::new(&DocumentType::ALL[..], Some(document.document_type), move |document_type| Message::UpdateDocumentType(i, document_type)))
+                .push(Button::new("Remove").on_press(Message::RemoveDocument(i))))
+            .push(input("Document Number", &document.number, DocumentField::Number).password())
+            .push(Row::new()
+                .spacing(5)
+                .push(input("Issuing Country", &document.issuing_country, DocumentField::IssuingCountry))
+                .push(input("Issuing Authority", &document.issuing_authority, DocumentField::IssuingAuthority)))
+            .push(Row::new()
+                .spacing(5)
+                .push(input("Issued (YYYY-MM-DD)", &document.issued, DocumentField::Issued))
+                .push(input("Expires (YYYY-MM-DD)", &document.expires, DocumentField::Expires)));
+        if let Some(error) = document_validation_error(&document) {
+            col = col.push(Text::new(error).size(14));
+        }
+    }
+    col.push(Button::new("Add document").on_press(Message::AddDocument))
+}
+
+// Custom fields in view mode, hidden ones stay encrypted until the entry is revealed
+fn view_custom_fields(style: StyleType, entry_data_edits: &Value, context: &DetailsContext) -> Column<'static, Message> {
+    let revealed_fields = context.revealed.map(custom_fields);
+    let mut col = Column::new().spacing(10);
+    for (i, field) in custom_fields(entry_data_edits).into_iter().enumerate() {
+        let value: Element<'static, Message> = match field.field_type {
+            CustomFieldType::Hidden => {
+                let shown = revealed_fields.as_ref().and_then(|fields| fields.get(i)).map(|field| field.value.clone());
+                Row::new()
+                    .spacing(5)
+                    .push(Text::new(shown.unwrap_or_else(|| String::from("Hidden"))).size(16))
+                    .push(Button::new(if context.revealed.is_some() { "Hide" } else { "Show" }).on_press(Message::ToggleRevealSecrets))
+                    .push(Button::new("Copy").on_press(Message::CopyCustomField(i)))
+                    .into()
+            },
+            // Only time based codes, the HOTP counter button is tied to the login's own OTP field
+            CustomFieldType::Totp => match OtpAuth::parse(&totp_uri(&field.value)) {
+                Ok(OtpAuth { kind: OtpKind::Totp { .. }, .. }) => view_otp(style, &totp_uri(&field.value), context.current_time).into(),
+                Ok(_) => Text::new("Only time based codes are supported here").size(14).into(),
+                Err(e) => Text::new(format!("{} ({})", field.value, e)).size(14).into(),
+            },
+            _ => {
+                let mut row = Row::new()
+                    .spacing(5)
+                    .push(Text::new(field.value.clone()).size(16))
+                    .push(Button::new("Copy").on_press(Message::CopyToClipboard(field.value.clone())));
+                if let Some(error) = validation_error(&field) {
+                    row = row.push(item_list::view_badge(style, error.to_string()));
+                }
+                row.into()
+            },
+        };
+        col = col
+            .push(Text::new(format!("{}: ", field.label)).size(16))
+            .push(value);
+    }
+    col
+}
+
+// Editable custom fields: label, type and value, in the order they're listed
+fn view_custom_field_editor(entry_data_edits: &Value) -> Column<'static, Message> {
+    let fields = custom_fields(entry_data_edits);
+    let last = fields.len().saturating_sub(1);
+    let mut col = Column::new()
+        .spacing(5)
+        .padding(5)
+        .push(Text::new("Custom fields").size(16));
+    for (i, field) in fields.into_iter().enumerate() {
+        let mut value = TextInput::new("Value", &field.value)
+            .padding(8)
+            .on_input(move |input| Message::UpdateCustomFieldValue(i, input));
+        if field.field_type == CustomFieldType::Hidden {
+            value = value.password();
+        }
+        let mut up_btn = Button::new("Up");
+        if i > 0 {
+            up_btn = up_btn.on_press(Message::MoveCustomField(i, true));
+        }
+        let mut down_btn = Button::new("Down");
+        if i < last {
+            down_btn = down_btn.on_press(Message::MoveCustomField(i, false));
+        }
+
+        col = col
+            .push(Row::new()
+                .spacing(5)
+                .push(TextInput::new("Label", &field.label)
+                    .padding(8)
+                    .on_input(move |input| Message::UpdateCustomFieldLabel(i, input)))
+                .push(PickList::new(&CustomFieldType::ALL[..], Some(field.field_type), move |field_type| Message::UpdateCustomFieldType(i, field_type))))
+            .push(value)
+            .push(Row::new()
+                .spacing(5)
+                .push(up_btn)
+                .push(down_btn)
+                .push(Button::new("Remove").on_press(Message::RemoveCustomField(i))));
+        if let Some(error) = validation_error(&field) {
+            col = col.push(Text::new(error).size(14));
+        }
+    }
+    col.push(Button::new("Add field").on_press(Message::AddCustomField))
+}
+
+// Attached files, downloaded in view mode and added or removed while editing
+fn view_attachments(entry_data_edits: &Value, context: &DetailsContext, editing: bool) -> Column<'static, Message> {
+    let attachments = attachment::attachments(entry_data_edits);
+    let mut col = Column::new()
+        .spacing(5)
+        .padding(5)
+        .push(Text::new("Attachments: ").size(16));
+    for (i, file) in attachments.iter().enumerate() {
+        let action = if editing {
+            Button::new("Remove").on_press(Message::RemoveAttachment(i))
+        } else {
+            Button::new("Save").on_press(Message::SaveAttachment(i))
+        };
+        col = col.push(Row::new()
+            .spacing(5)
+            .push(Text::new(format!("{} ({})", file.name, attachment::format_size(file.size))).size(14))
+            .push(action));
+    }
+
+    if editing {
+        col = col.push(Row::new()
+            .spacing(5)
+            .push(TextInput::new("File to attach", context.attachment_path)
+                .padding(8)
+                .on_input(Message::UpdateAttachmentPath)
+                .on_submit(Message::AddAttachment))
+            .push(Button::new("Attach").padding(8).on_press(Message::AddAttachment)));
+    } else if attachments.is_empty() {
+        col = col.push(Text::new("None").size(14));
+    } else {
+        col = col.push(TextInput::new("Folder or file to save to", context.attachment_path)
+            .padding(8)
+            .on_input(Message::UpdateAttachmentPath));
+    }
+    col.push(Text::new(context.attachment_status.to_string()).size(14))
+}
+
+// "Move to folder" menu, listing every folder by its full path
+fn view_folder_menu(entry_data_edits: &Value, folder_choices: &[FolderChoice]) -> Row<'static, Message> {
+    let folder = entry_folder(entry_data_edits);
+    let selected = folder_choices.iter().find(|choice| choice.id == folder).cloned();
+    Row::new()
+        .spacing(5)
+        .padding(5)
+        .push(Text::new("Folder: ").size(16))
+        .push(PickList::new(folder_choices.to_vec(), selected, Message::MoveToFolder))
+}
+
+// Live one-time code for an otpauth URI: TOTP codes count down, HOTP codes advance on demand
+fn view_otp(style: StyleType, uri: &str, current_time: u64) -> Column<'static, Message> {
+    let otp = match OtpAuth::parse(uri) {
+        Ok(otp) => otp,
+        Err(e) => {
+            let mut col = Column::new().push(Text::new(uri.to_string()).size(16));
+            if !uri.is_empty() {
+                col = col.push(Text::new(format!("({})", e)).size(14));
+            }
+            return col;
+        },
+    };
+
+    let (code, remaining) = otp.current_code(current_time);
+    let copy_btn = Button::new("Copy")
+        .on_press(Message::CopyToClipboard(code.clone()))
+        .style(<StyleTuple as Into<iced::theme::Button>>::into(
+            StyleTuple(style, ElementType::Button),
+        ));
+
+    let mut col = Column::new()
+        .spacing(5)
+        .push(Row::new()
+            .spacing(10)
+            .push(Text::new(totp::format_code(&code)).size(24))
+            .push(copy_btn));
+
+    match (otp.kind, remaining) {
+        (OtpKind::Totp { period }, Some(remaining)) => {
+            col = col
+                .push(ProgressBar::new(0.0..=period as f32, remaining as f32).height(Length::Fixed(6.0)))
+                .push(Text::new(format!("Refreshes in {}s", remaining)).size(14));
+        },
+        (OtpKind::Hotp { counter }, _) => {
+            let next_btn = Button::new("Next code")
+                .on_press(Message::IncrementHotpCounter)
+                .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                    StyleTuple(style, ElementType::Button),
+                ));
+            col = col
+                .push(Text::new(format!("Counter: {}", counter)).size(14))
+                .push(next_btn);
+        },
+        _ => (),
+    }
+
+    if !otp.issuer.is_empty() || !otp.account.is_empty() {
+        col = col.push(Text::new(format!("{} {}", otp.issuer, otp.account)).size(14));
+    }
+    col
+}
+
+// Tags shown as chips
+fn view_tags(style: StyleType, entry_data: &Value) -> Column<'static, Message> {
+    chip_rows(entry_tags(entry_data).into_iter().map(|tag| {
+        Container::new(Text::new(tag).size(14))
+            .padding(4)
+            .style(<StyleTuple as Into<iced::theme::Container>>::into(
+                StyleTuple(style, ElementType::PrimaryAccent),
+            ))
+            .into()
+    }).collect())
+}
+
+// Rows don't wrap, so lay chips out a few per row to fit the details column
+fn chip_rows(chips: Vec<Element<'static, Message>>) -> Column<'static, Message> {
+    let mut col = Column::new().spacing(5);
+    let mut row = Row::new().spacing(5);
+    for (i, chip) in chips.into_iter().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            col = col.push(row);
+            row = Row::new().spacing(5);
+        }
+        row = row.push(chip);
+    }
+    col.push(row)
+}
+
+// Removable tag chips, an input for new tags and autocomplete from the tags already in use
+fn view_tag_editor(style: StyleType, entry_data_edits: &Value, context: &DetailsContext) -> Column<'static, Message> {
+    let tags = entry_tags(entry_data_edits);
+
+    let chips = chip_rows(tags.iter().map(|tag| {
+        Button::new(Text::new(format!("{} x", tag)).size(14))
+            .padding(4)
+            .on_press(Message::RemoveTag(tag.clone()))
+            .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                StyleTuple(style, ElementType::Button),
+            ))
+            .into()
+    }).collect());
+
+    let input = TextInput::new("Add tag", context.tag_input)
+        .padding(8)
+        .on_input(Message::UpdateTagInput)
+        .on_submit(Message::AddTag(context.tag_input.to_string()));
+
+    // Tags starting with the input come first, then tags containing it
+    let query = context.tag_input.trim().to_lowercase();
+    let mut suggestions: Vec<&String> = context.known_tags
+        .iter()
+        .filter(|known| !query.is_empty() && known.to_lowercase().contains(&query))
+        .filter(|known| !tags.iter().any(|tag| tag.eq_ignore_ascii_case(known)))
+        .collect();
+    suggestions.sort_by_key(|known| !known.to_lowercase().starts_with(&query));
+
+    let suggestion_rows = chip_rows(suggestions.into_iter().take(6).map(|known| {
+        Button::new(Text::new(known.clone()).size(14))
+            .padding(4)
+            .on_press(Message::AddTag(known.clone()))
+            .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                StyleTuple(style, ElementType::ItemListEntry),
+            ))
+            .into()
+    }).collect());
+
+    Column::new()
+        .spacing(5)
+        .push(chips)
+        .push(input)
+        .push(suggestion_rows)
+}
@@ -0,0 +1,67 @@
+use iced::Length;
+use iced::widget::{Column, Container, Text, Button, Scrollable};
+use iced::Element;
+use serde_json::Value;
+
+// Import Message enum from the main application module
+use crate::gui::core::message::Message;
+use crate::gui::pages::details_page::EntryType;
+use crate::gui::styles::types::{
+    style_type::StyleType,
+    style_tuple::StyleTuple,
+    element_type::ElementType,
+};
+use crate::vault::entry::is_favorite;
+use crate::vault::search::field_text;
+
+// Define the user interface layout for the FavoritesPage, listing favorites of every entry type
+pub fn view_page(style: StyleType, vault: &Value, selected_entry_type: EntryType, selected_entry_id: i32) -> Element<'static, Message> {
+    // Create a text label for the FavoritesPage
+    let label = Text::new("Favorites");
+
+    let favorite_entry = |entry_type: EntryType, entry_id: i32, title: String, subtitle: String| {
+        Button::new(
+            Column::new()
+                .push(Text::new(entry_type.label()).size(14))
+                .push(Text::new(title))
+                .push(Text::new(subtitle))
+        )
+            .padding(25)
+            .width(Length::Fill)
+            .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                if entry_type == selected_entry_type && entry_id == selected_entry_id {
+                    StyleTuple(style, ElementType::SelectedItem)
+                } else {
+                    StyleTuple(style, ElementType::ItemListEntry)
+                }
+            ))
+            .on_press(Message::SelectEntryOfType(entry_type, entry_id))
+    };
+
+    let mut col = Column::new().push(label);
+    let mut empty = true;
+    for entry_type in EntryType::ALL {
+        let entries = vault[entry_type.as_str()].as_array().cloned().unwrap_or_default();
+        let subtitle_field = entry_type.subtitle_field();
+        for (index, value) in entries.iter().enumerate().filter(|(_, value)| is_favorite(value)) {
+            empty = false;
+            col = col.push(favorite_entry(entry_type, index as i32, field_text(&value["title"]), field_text(&value[subtitle_field])));
+        }
+    }
+    if empty {
+        col = col.push(Text::new("No favorites yet, mark an entry as favorite to pin it here"));
+    }
+
+    let scroll_area = Scrollable::new(col);
+
+    // Create a container to hold the column layout, set its dimensions and position, and return it as an Element
+    Container::new(scroll_area)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .style(<StyleTuple as Into<iced::theme::Container>>::into(
+            StyleTuple(style, ElementType::ItemListColumn),
+        ))
+        .into()
+}
@@ -0,0 +1,126 @@
+use iced::Length;
+use iced::widget::{Column, Container, Text, Button, Row, Radio, Slider, Checkbox, TextInput, ProgressBar};
+use iced::Element;
+
+// Import Message enum from the main application module
+use crate::gui::core::message::Message;
+use crate::gui::styles::types::{
+    style_type::StyleType,
+    style_tuple::StyleTuple,
+    element_type::ElementType,
+};
+use crate::secure::generator::{
+    GeneratorMode,
+    GeneratorOptions,
+    MAX_PASSWORD_LENGTH,
+    MAX_WORD_COUNT,
+    MIN_PASSWORD_LENGTH,
+    MIN_WORD_COUNT,
+};
+use crate::secure::strength::{self, StrengthEstimate};
+
+// Define the user interface layout for the GeneratorPage
+pub fn view_page(style: StyleType, options: &GeneratorOptions, generated_password: &str) -> Element<'static, Message> {
+    // Create a text label for the GeneratorPage
+    let label = Text::new("Password Generator");
+
+    let generated = TextInput::new("", generated_password)
+        .padding(8);
+
+    let regenerate_btn = Button::new("Regenerate")
+        .padding(8)
+        .on_press(Message::RegeneratePassword)
+        .style(<StyleTuple as Into<iced::theme::Button>>::into(
+            StyleTuple(style, ElementType::Button),
+        ));
+    let copy_btn = Button::new("Copy")
+        .padding(8)
+        .on_press(Message::CopyGeneratedPassword)
+        .style(<StyleTuple as Into<iced::theme::Button>>::into(
+            StyleTuple(style, ElementType::Button),
+        ));
+
+    let output_row = Row::new()
+        .spacing(5)
+        .push(generated)
+        .push(regenerate_btn)
+        .push(copy_btn);
+
+    let col = Column::new()
+        .spacing(15)
+        .max_width(500)
+        .push(label)
+        .push(output_row)
+        .push(view_strength(&strength::estimate(generated_password)))
+        .push(view_options(options));
+
+    // Create a container to hold the column layout, set its dimensions and position, and return it as an Element
+    Container::new(col)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(20)
+        .center_x()
+        .center_y()
+        .style(<StyleTuple as Into<iced::theme::Container>>::into(
+            StyleTuple(style, ElementType::ItemListColumn),
+        ))
+        .into()
+}
+
+// Generator option controls, shared with the password field of the details page
+pub fn view_options(options: &GeneratorOptions) -> Column<'static, Message> {
+    let mode_row = Row::new()
+        .spacing(15)
+        .push(Radio::new("Password", GeneratorMode::Password, Some(options.mode), Message::ChangeGeneratorMode))
+        .push(Radio::new("Passphrase", GeneratorMode::Passphrase, Some(options.mode), Message::ChangeGeneratorMode));
+
+    let col = Column::new()
+        .spacing(10)
+        .push(mode_row);
+
+    match options.mode {
+        GeneratorMode::Password => {
+            let length = Column::new()
+                .push(Text::new(format!("Length: {}", options.length)))
+                .push(Slider::new(MIN_PASSWORD_LENGTH..=MAX_PASSWORD_LENGTH, options.length, Message::UpdateGeneratorLength));
+
+            col.push(length)
+                .push(Checkbox::new("Lowercase (a-z)", options.lowercase, Message::ToggleGeneratorLowercase))
+                .push(Checkbox::new("Uppercase (A-Z)", options.uppercase, Message::ToggleGeneratorUppercase))
+                .push(Checkbox::new("Digits (0-9)", options.digits, Message::ToggleGeneratorDigits))
+                .push(Checkbox::new("Symbols (!@#$...)", options.symbols, Message::ToggleGeneratorSymbols))
+                .push(Checkbox::new("Exclude ambiguous (Il1|O0o)", options.exclude_ambiguous, Message::ToggleGeneratorExcludeAmbiguous))
+                .push(Checkbox::new("Require every selected class", options.require_each_class, Message::ToggleGeneratorRequireEachClass))
+        },
+        GeneratorMode::Passphrase => {
+            let word_count = Column::new()
+                .push(Text::new(format!("Words: {}", options.word_count)))
+                .push(Slider::new(MIN_WORD_COUNT..=MAX_WORD_COUNT, options.word_count, Message::UpdateGeneratorWordCount));
+
+            let separator = TextInput::new("Separator", &options.separator)
+                .padding(8)
+                .on_input(Message::UpdateGeneratorSeparator);
+
+            col.push(word_count)
+                .push(separator)
+                .push(Checkbox::new("Capitalize words", options.capitalize, Message::ToggleGeneratorCapitalize))
+                .push(Checkbox::new("Include a number", options.include_number, Message::ToggleGeneratorIncludeNumber))
+        },
+    }
+}
+
+// Strength meter with warnings and suggestions, shared with the details page and login screen
+pub fn view_strength(estimate: &StrengthEstimate) -> Column<'static, Message> {
+    let mut col = Column::new()
+        .spacing(5)
+        .push(ProgressBar::new(0.0..=4.0, estimate.score as f32).height(Length::Fixed(8.0)))
+        .push(Text::new(format!("{} (~{:.0} bits)", estimate.label(), estimate.entropy_bits)).size(16));
+
+    for warning in &estimate.warnings {
+        col = col.push(Text::new(format!("! {}", warning)).size(14));
+    }
+    for suggestion in &estimate.suggestions {
+        col = col.push(Text::new(format!("- {}", suggestion)).size(14));
+    }
+    col
+}
@@ -1,28 +1,37 @@
 use iced::Length;
-use iced::widget::{Column, Container, Text, Button, Scrollable};
+use iced::widget::{Column, Container, Text, Button, Scrollable, Row};
 use iced::Element;
 use serde_json::Value;
 
 // Import Message enum from the main application module
 use crate::gui::core::message::Message;
+use crate::gui::pages::details_page::EntryType;
+use crate::gui::pages::item_list;
 use crate::gui::styles::types::{
     style_type::StyleType,
     style_tuple::StyleTuple,
     element_type::ElementType,
 };
+use crate::vault::entry::is_favorite;
+use crate::vault::expiry::{entry_expiry, ExpiryWindow};
+use crate::vault::search::{search_entries, filter_entries, field_text, EntryFilter};
+use crate::vault::sort::{arrange, SortPreference};
 
 // Define the user interface layout for the IdentitiesPage
-pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32) -> Element<'static, Message> {
+pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32, search_query: &str, sort: SortPreference, filter: &EntryFilter, expiry: ExpiryWindow) -> Element<'static, Message> {
     // Create a text label for the IdentitiesPage
     let label = Text::new("Identities page");
 
-    let identity_entry = |entry_id: i32, title: String, first_name: String, last_name: String, email: String| {
-        Button::new(
-            Column::new()
-                .push(Text::new(title))
-                .push(Text::new(format!("{} {}", first_name, last_name)))
-                .push(Text::new(email))
-        )
+    let identity_entry = |entry_id: i32, title: Row<'static, Message>, first_name: Row<'static, Message>, last_name: Row<'static, Message>, email: Row<'static, Message>, expiry_warning: Option<String>| {
+        let mut content = Column::new()
+            .push(title)
+            .push(Row::new().push(first_name).push(Text::new(" ")).push(last_name))
+            .push(email);
+        // Flags a passport or licence that needs renewing
+        if let Some(warning) = expiry_warning {
+            content = content.push(item_list::view_badge(style, warning));
+        }
+        Button::new(content)
             .padding(25)
             .width(Length::Fill)
             .style(<StyleTuple as Into<iced::theme::Button>>::into(
@@ -35,24 +44,31 @@ pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32) -> E
             .on_press(Message::SelectEntry(entry_id))
     };
 
-    // Create a column layout, add the label and button to it
-    let mut col = Column::new().push(label);
-    let get_val = |value: &Value, label: &str| {
-        let s = serde_json::to_string(&value[label]).unwrap();
-        s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).map(|s| s.to_owned()).unwrap_or_else(|| s.to_owned())
-    };
-    let mut counter: i32 = 0;
-    if let Some(obj) = entries.as_array() {
-        for value in obj {
+    // Create a column layout, add the label, toolbar and matching entries to it
+    let mut col = Column::new()
+        .push(label)
+        .push(item_list::view_toolbar(search_query, sort));
+    let searching = !search_query.trim().is_empty();
+    let matches = filter_entries(entries, search_entries(entries, EntryType::Identities, search_query), filter);
+    if matches.is_empty() && searching {
+        col = col.push(Text::new("No matches"));
+    }
+    for (group, matches) in arrange(entries, matches, sort, searching) {
+        if let Some(header) = item_list::view_group_header(&group) {
+            col = col.push(header);
+        }
+        for found in matches {
+            let value = &entries[found.index];
+            let field = |label: &'static str| item_list::highlighted_text(style, field_text(&value[label]), found.highlights.get(label));
             col = col.push(
-                identity_entry(counter,
-                    get_val(value, "title"),
-                    get_val(value, "first_name"),
-                    get_val(value, "last_name"),
-                    get_val(value, "phone"),
+                identity_entry(found.index as i32,
+                    item_list::with_favorite_marker(style, field("title"), is_favorite(value)),
+                    field("first_name"),
+                    field("last_name"),
+                    field("phone"),
+                    entry_expiry(EntryType::Identities, value, expiry).map(|(document, status)| format!("{}: {}", document, status.warning().unwrap_or_default())),
                 )
             );
-            counter += 1;
         }
     }
 
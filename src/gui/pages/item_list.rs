@@ -0,0 +1,93 @@
+use iced::widget::{Column, Container, Text, Row, TextInput, PickList, Button};
+use iced::Color;
+
+// Import Message enum from the main application module
+use crate::gui::core::message::Message;
+use crate::gui::styles::style_constants::RALEWAY_BOLD;
+use crate::gui::styles::types::{
+    element_type::ElementType,
+    palette::get_colors,
+    style_tuple::StyleTuple,
+    style_type::StyleType,
+};
+use crate::vault::sort::{Grouping, SortOrder, SortPreference};
+
+// Search box, sort controls and the new entry button shown above the item lists
+pub fn view_toolbar(search_query: &str, sort: SortPreference) -> Column<'static, Message> {
+    Column::new()
+        .spacing(5)
+        .padding(5)
+        .push(view_search_bar(search_query))
+        .push(Row::new()
+            .spacing(5)
+            .push(PickList::new(&SortOrder::ALL[..], Some(sort.order), Message::ChangeSortOrder))
+            .push(PickList::new(&Grouping::ALL[..], Some(sort.grouping), Message::ChangeGrouping))
+            .push(Button::new("New").on_press(Message::NewEntry)))
+}
+
+pub fn view_search_bar(search_query: &str) -> TextInput<'static, Message> {
+    TextInput::new("Search...", search_query)
+        .padding(8)
+        .on_input(Message::UpdateSearchQuery)
+}
+
+// Header above each group of entries, nothing when the list isn't grouped
+pub fn view_group_header(label: &str) -> Option<Text<'static>> {
+    if label.is_empty() {
+        None
+    } else {
+        Some(Text::new(label.to_string()).font(RALEWAY_BOLD).size(18))
+    }
+}
+
+// Text with the matched characters emphasized in the accent color
+pub fn highlighted_text(style: StyleType, text: String, indices: Option<&Vec<usize>>) -> Row<'static, Message> {
+    let indices = match indices {
+        Some(indices) if !indices.is_empty() => indices,
+        _ => return Row::new().push(Text::new(text)),
+    };
+
+    let accent = get_colors(style).primary_accent;
+    let mut row = Row::new();
+    let mut run = String::new();
+    let mut run_highlighted = false;
+    for (i, c) in text.chars().enumerate() {
+        let highlighted = indices.binary_search(&i).is_ok();
+        if highlighted != run_highlighted && !run.is_empty() {
+            row = row.push(text_run(std::mem::take(&mut run), run_highlighted, accent));
+        }
+        run_highlighted = highlighted;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        row = row.push(text_run(run, run_highlighted, accent));
+    }
+    row
+}
+
+fn text_run(run: String, highlighted: bool, accent: Color) -> Text<'static> {
+    if highlighted {
+        Text::new(run).font(RALEWAY_BOLD).style(accent)
+    } else {
+        Text::new(run)
+    }
+}
+
+// Prefix a title with a star when the entry is a favorite
+pub fn with_favorite_marker(style: StyleType, title: Row<'static, Message>, favorite: bool) -> Row<'static, Message> {
+    if !favorite {
+        return title;
+    }
+    Row::new()
+        .push(Text::new("* ").font(RALEWAY_BOLD).style(get_colors(style).primary_accent))
+        .push(title)
+}
+
+// Small highlighted label, e.g. an expiry warning
+pub fn view_badge(style: StyleType, label: String) -> Container<'static, Message> {
+    Container::new(Text::new(label).size(14))
+        .padding(4)
+        .style(<StyleTuple as Into<iced::theme::Container>>::into(
+            StyleTuple(style, ElementType::PrimaryAccent),
+        ))
+}
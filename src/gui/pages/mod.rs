@@ -1,6 +1,15 @@
+pub mod api_credentials_page;
+pub mod bank_accounts_page;
 pub mod cards_page;
 pub mod details_page;
+pub mod favorites_page;
+pub mod generator_page;
 pub mod identities_page;
+pub mod item_list;
 pub mod nav_page;
+pub mod notes_page;
 pub mod passwords_page;
-pub mod profile_page;
\ No newline at end of file
+pub mod profile_page;
+pub mod search_page;
+pub mod ssh_keys_page;
+pub mod wifi_networks_page;
\ No newline at end of file
@@ -1,6 +1,7 @@
 use iced::Length;
-use iced::widget::{Column, Container, Text, Button};
+use iced::widget::{Column, Container, Text, Button, Scrollable, Row, TextInput};
 use iced::Element;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::gui::core::app::Pages;
 // Import Message enum from the main application module
@@ -16,9 +17,25 @@ use crate::gui::styles::types::{
     style_tuple::StyleTuple,
     element_type::ElementType,
 };
+use crate::vault::folder::FolderNode;
+
+/// Tag and folder browser state shown below the page buttons
+pub struct NavContext<'a> {
+    pub tags: &'a BTreeMap<String, usize>,
+    pub selected_tags: &'a BTreeSet<String>,
+    pub show_tags: bool,
+    /// Folder tree with the number of entries directly inside each folder
+    pub folders: Vec<(FolderNode, usize)>,
+    pub selected_folder: Option<u64>,
+    pub show_folders: bool,
+    pub folder_input: &'a str,
+    pub folder_error: &'a str,
+    /// Entries in the selected folder and its subfolders, while deleting them waits for confirmation
+    pub pending_delete: Option<usize>,
+}
 
 // Define the user interface layout for the ProfilePage
-pub fn view_page(style: StyleType, current_page: Pages) -> Element<'static, Message> {
+pub fn view_page(style: StyleType, current_page: Pages, context: &NavContext) -> Element<'static, Message> {
     let nav_btn = |label, page| {
         Button::new(
             Text::new(label)
@@ -48,18 +65,143 @@ pub fn view_page(style: StyleType, current_page: Pages) -> Element<'static, Mess
             StyleTuple(style, ElementType::NavHeader),
         ));
     let profile_page_btn = nav_btn("Profile", Pages::ProfilePage);
+    let search_page_btn = nav_btn("Search", Pages::SearchPage);
+    let favorites_page_btn = nav_btn("Favorites", Pages::FavoritesPage);
     let passwords_page_btn = nav_btn("Passwords", Pages::PasswordsPage);
     let identities_page_btn = nav_btn("Identities", Pages::IdentitiesPage);
     let cards_page_btn = nav_btn("Cards", Pages::CardsPage);
+    let notes_page_btn = nav_btn("Secure Notes", Pages::NotesPage);
+    let ssh_keys_page_btn = nav_btn("SSH Keys", Pages::SshKeysPage);
+    let api_credentials_page_btn = nav_btn("API Credentials", Pages::ApiCredentialsPage);
+    let bank_accounts_page_btn = nav_btn("Bank Accounts", Pages::BankAccountsPage);
+    let wifi_networks_page_btn = nav_btn("Wi-Fi Networks", Pages::WifiNetworksPage);
+    let generator_page_btn = nav_btn("Generator", Pages::GeneratorPage);
+
+    // Collapsible folder tree, selecting a folder shows its entries and those of its subfolders
+    let section_toggle_btn = |label: &'static str, message: Message| {
+        Button::new(
+            Text::new(label)
+                .font(RALEWAY_BOLD)
+                .size(FONT_SIZE_NAV)
+            )
+            .width(Length::Fixed(200.0))
+            .padding(10)
+            .on_press(message)
+            .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                StyleTuple(style, ElementType::NavButton),
+            ))
+    };
+    let folder_btn = |label: String, folder: Option<u64>, depth: usize| {
+        Button::new(Text::new(label))
+            .width(Length::Fixed(200.0))
+            .padding([5, 10, 5, 25 + 15 * depth as u16])
+            .on_press(Message::SelectFolder(folder))
+            .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                if context.selected_folder == folder {
+                    StyleTuple(style, ElementType::NavButtonSelected)
+                } else {
+                    StyleTuple(style, ElementType::NavButton)
+                }
+            ))
+    };
+    let folders_toggle_btn = section_toggle_btn(if context.show_folders { "Folders -" } else { "Folders +" }, Message::ToggleFolderSection);
+    let mut folder_list = Column::new();
+    if context.show_folders {
+        folder_list = folder_list.push(folder_btn(String::from("All entries"), None, 0));
+        for (node, count) in &context.folders {
+            folder_list = folder_list.push(folder_btn(format!("{} ({})", node.folder.name, count), Some(node.folder.id), node.depth));
+        }
+
+        // New folders are created inside the selected folder
+        let mut actions = Row::new()
+            .spacing(5)
+            .push(Button::new(Text::new("New").size(14)).on_press(Message::CreateFolder));
+        if context.selected_folder.is_some() {
+            actions = actions
+                .push(Button::new(Text::new("Rename").size(14)).on_press(Message::RenameFolder))
+                .push(Button::new(Text::new("Delete").size(14)).on_press(Message::DeleteFolder(false)));
+        }
+        folder_list = folder_list.push(
+            Column::new()
+                .width(Length::Fixed(200.0))
+                .spacing(5)
+                .padding([5, 10, 5, 25])
+                .push(TextInput::new("Folder name", context.folder_input)
+                    .padding(5)
+                    .on_input(Message::UpdateFolderInput)
+                    .on_submit(Message::CreateFolder))
+                .push(actions)
+        );
+        if let Some(count) = context.pending_delete {
+            folder_list = folder_list.push(
+                Column::new()
+                    .width(Length::Fixed(200.0))
+                    .spacing(5)
+                    .padding([5, 10, 5, 25])
+                    .push(Text::new(format!("Delete this folder, its subfolders and the {} entries in them? This can't be undone.", count)).size(14))
+                    .push(Row::new()
+                        .spacing(5)
+                        .push(Button::new(Text::new("Delete").size(14)).on_press(Message::DeleteFolder(true)))
+                        .push(Button::new(Text::new("Cancel").size(14)).on_press(Message::ConfirmFolderDelete(false))))
+            );
+        } else if context.selected_folder.is_some() {
+            folder_list = folder_list.push(
+                Button::new(Text::new("Delete with entries").size(14))
+                    .padding([5, 10, 5, 25])
+                    .on_press(Message::ConfirmFolderDelete(true))
+            );
+        }
+        if !context.folder_error.is_empty() {
+            folder_list = folder_list.push(Text::new(context.folder_error.to_string()).size(14));
+        }
+    }
+
+    // Collapsible tag browser, selected tags filter the item lists
+    let tags_toggle_btn = section_toggle_btn(if context.show_tags { "Tags -" } else { "Tags +" }, Message::ToggleTagSection);
+    let mut tag_list = Column::new();
+    if context.show_tags {
+        // Selected tags no entry has any more stay listed with a count of 0, so they can be cleared
+        let orphaned = context.selected_tags.iter().filter(|tag| !context.tags.contains_key(*tag)).map(|tag| (tag, &0));
+        for (tag, count) in context.tags.iter().chain(orphaned) {
+            tag_list = tag_list.push(
+                Button::new(Text::new(format!("{} ({})", tag, count)))
+                    .width(Length::Fixed(200.0))
+                    .padding([5, 10, 5, 25])
+                    .on_press(Message::ToggleTagFilter(tag.clone()))
+                    .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                        if context.selected_tags.contains(tag) {
+                            StyleTuple(style, ElementType::NavButtonSelected)
+                        } else {
+                            StyleTuple(style, ElementType::NavButton)
+                        }
+                    ))
+            );
+        }
+    }
 
     // Create nav container
     Container::new(
         Column::new()
             .push(keybolt_title)
             .push(profile_page_btn)
+            .push(search_page_btn)
+            .push(favorites_page_btn)
             .push(passwords_page_btn)
             .push(identities_page_btn)
             .push(cards_page_btn)
+            .push(notes_page_btn)
+            .push(ssh_keys_page_btn)
+            .push(api_credentials_page_btn)
+            .push(bank_accounts_page_btn)
+            .push(wifi_networks_page_btn)
+            .push(generator_page_btn)
+            .push(Scrollable::new(
+                Column::new()
+                    .push(folders_toggle_btn)
+                    .push(folder_list)
+                    .push(tags_toggle_btn)
+                    .push(tag_list)
+            ).height(Length::Fill))
     ).height(iced::Length::Fill)
     .style(<StyleTuple as Into<iced::theme::Container>>::into(
         StyleTuple(style, ElementType::NavColumn),
@@ -1,25 +1,30 @@
 use iced::Length;
-use iced::widget::{Column, Container, Text, Button, Scrollable};
+use iced::widget::{Column, Container, Text, Button, Scrollable, Row};
 use iced::Element;
 use serde_json::Value;
 
 // Import Message enum from the main application module
 use crate::gui::core::message::Message;
+use crate::gui::pages::details_page::EntryType;
+use crate::gui::pages::item_list;
 use crate::gui::styles::types::{
     style_type::StyleType,
     style_tuple::StyleTuple,
     element_type::ElementType,
 };
+use crate::vault::entry::is_favorite;
+use crate::vault::search::{search_entries, filter_entries, field_text, EntryFilter};
+use crate::vault::sort::{arrange, SortPreference};
 
 // Define the user interface layout for the PasswordsPage
-pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32) -> Element<'static, Message> {
+pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32, search_query: &str, sort: SortPreference, filter: &EntryFilter) -> Element<'static, Message> {
     // Create a text label for the PasswordsPage
     let label = Text::new("Passwords page");
-    let password_entry = |entry_id: i32, label: String, username: String| {
+    let password_entry = |entry_id: i32, label: Row<'static, Message>, username: Row<'static, Message>| {
         Button::new(
             Column::new()
-                .push(Text::new(label))
-                .push(Text::new(username))
+                .push(label)
+                .push(username)
         )
             .padding(25)
             .width(Length::Fill)
@@ -33,22 +38,27 @@ pub fn view_page(style: StyleType, entries: &Value, selected_entry_id: i32) -> E
             .on_press(Message::SelectEntry(entry_id))
     };
 
-    // Create a column layout, add the label and button to it
-    let mut col = Column::new().push(label);
-    let get_val = |value: &Value, label: &str| {
-        let s = serde_json::to_string(&value[label]).unwrap();
-        s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).map(|s| s.to_owned()).unwrap_or_else(|| s.to_owned())
-    };
-    let mut counter = 0;
-    if let Some(obj) = entries.as_array() {
-        for value in obj {
+    // Create a column layout, add the label, toolbar and matching entries to it
+    let mut col = Column::new()
+        .push(label)
+        .push(item_list::view_toolbar(search_query, sort));
+    let searching = !search_query.trim().is_empty();
+    let matches = filter_entries(entries, search_entries(entries, EntryType::Passwords, search_query), filter);
+    if matches.is_empty() && searching {
+        col = col.push(Text::new("No matches"));
+    }
+    for (group, matches) in arrange(entries, matches, sort, searching) {
+        if let Some(header) = item_list::view_group_header(&group) {
+            col = col.push(header);
+        }
+        for found in matches {
+            let value = &entries[found.index];
             col = col.push(
-                password_entry(counter,
-                    get_val(value, "title"),
-                    get_val(value, "username"),
+                password_entry(found.index as i32,
+                    item_list::with_favorite_marker(style, item_list::highlighted_text(style, field_text(&value["title"]), found.highlights.get("title")), is_favorite(value)),
+                    item_list::highlighted_text(style, field_text(&value["username"]), found.highlights.get("username")),
                 ),
             );
-            counter += 1;
         }
     }
 
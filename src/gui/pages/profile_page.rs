@@ -1,17 +1,44 @@
 use iced::Length;
-use iced::widget::{Column, Container, Text, Button};
+use iced::widget::{Column, Container, Text, Button, Row, PickList, Scrollable, TextInput, Checkbox};
 use iced::Element;
+use serde_json::Value;
 
 // Import Message enum from the main application module
 use crate::gui::core::message::Message;
+use crate::gui::core::preferences::EXPIRY_WINDOW_CHOICES;
+use crate::gui::pages::item_list;
 use crate::gui::styles::types::{
     style_type::StyleType,
     style_tuple::StyleTuple,
     element_type::ElementType,
 };
+use crate::import::{ImportFormat, ImportPreview};
+use crate::import::kdbx::KdbxCipher;
+use crate::vault::date::ExpiryStatus;
+use crate::vault::expiry::{expiring_entries, ExpiryWindow};
+use crate::vault::search::field_text;
+
+// Longest list of previewed entries, the counts above it cover the rest
+const MAX_PREVIEW_ROWS: usize = 200;
+
+/// Import and export state, the file path is shared by every format
+pub struct TransferContext<'a> {
+    pub path: &'a str,
+    /// Outcome of the last import, export or preview
+    pub status: &'a str,
+    pub import_format: ImportFormat,
+    /// Entries read from the file, waiting to be confirmed
+    pub preview: Option<&'a ImportPreview>,
+    pub skip_duplicates: bool,
+    /// Password of the KeePass database being imported or exported
+    pub password: &'a str,
+    pub kdbx_cipher: KdbxCipher,
+    /// Command pass files are decrypted with
+    pub gpg_command: &'a str,
+}
 
 // Define the user interface layout for the ProfilePage
-pub fn view_page(style: StyleType) -> Element<'static, Message> {
+pub fn view_page(style: StyleType, vault: &Value, expiry: ExpiryWindow, transfer: &TransferContext) -> Element<'static, Message> {
     // Create a text label for the ProfilePage
     let label = Text::new("Profile Page");
     let fjord_mode_btn = Button::new("Fjord Mode")
@@ -44,10 +71,12 @@ pub fn view_page(style: StyleType) -> Element<'static, Message> {
         .push(fjord_mode_btn)
         .push(vibrant_mode_btn)
         .push(dark_mode_btn)
-        .push(default_mode_btn);
+        .push(default_mode_btn)
+        .push(view_expiring(style, vault, expiry))
+        .push(view_transfer(style, transfer));
 
     // Create a container to hold the column layout, set its dimensions and position, and return it as an Element
-    Container::new(col)
+    Container::new(Scrollable::new(col))
         .width(Length::Fill)
         .height(Length::Fill)
         .center_x()
@@ -56,4 +85,113 @@ pub fn view_page(style: StyleType) -> Element<'static, Message> {
             StyleTuple(style, ElementType::ItemListColumn),
         ))
         .into()
-}
\ No newline at end of file
+}
+// Dashboard of cards, credentials and documents that have expired or expire within the window
+fn view_expiring(style: StyleType, vault: &Value, expiry: ExpiryWindow) -> Column<'static, Message> {
+    let expiring = expiring_entries(vault, expiry);
+    let expired = expiring.iter().filter(|found| found.status == ExpiryStatus::Expired).count();
+
+    let window = Row::new()
+        .spacing(5)
+        .push(Text::new("Flag expiry dates this many days ahead:"))
+        .push(PickList::new(&EXPIRY_WINDOW_CHOICES[..], Some(expiry.warning_days), Message::ChangeExpiryWindow));
+
+    let mut col = Column::new()
+        .spacing(5)
+        .padding(15)
+        .push(Text::new("Expiring soon").size(20))
+        .push(window)
+        .push(Text::new(format!("{} expiring within {} days, {} expired", expiring.len() - expired, expiry.warning_days, expired)));
+    for found in expiring {
+        let warning = found.status.warning().unwrap_or_default();
+        col = col.push(
+            Button::new(Row::new()
+                .spacing(5)
+                .push(Text::new(format!("{} ({}, {})", found.title, found.entry_type.label(), found.field)))
+                .push(item_list::view_badge(style, warning)))
+                .width(Length::Fixed(400.0))
+                .on_press(Message::SelectEntryOfType(found.entry_type, found.index as i32))
+                .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                    StyleTuple(style, ElementType::ItemListEntry),
+                )),
+        );
+    }
+    col
+}
+
+// Importing and exporting the vault's entries to other apps' file formats
+fn view_transfer(style: StyleType, transfer: &TransferContext) -> Column<'static, Message> {
+    let mut col = Column::new()
+        .spacing(10)
+        .padding(10)
+        .push(Text::new("Import & export"))
+        .push(TextInput::new("File path, e.g. ~/contacts.vcf", transfer.path)
+            .padding(8)
+            .width(Length::Fixed(400.0))
+            .on_input(Message::UpdateTransferPath))
+        .push(Row::new()
+            .spacing(5)
+            .push(Button::new("Import vCards").on_press(Message::ImportVcards))
+            .push(Button::new("Export all identities").on_press(Message::ExportVcards(true))))
+        .push(Row::new()
+            .spacing(5)
+            .push(PickList::new(&ImportFormat::ALL[..], Some(transfer.import_format), Message::ChangeImportFormat))
+            .push(Button::new("Preview import").on_press(Message::PreviewImport)))
+        .push(Row::new()
+            .spacing(5)
+            .push(Text::new("gpg command for pass").size(14))
+            .push(TextInput::new("gpg", transfer.gpg_command)
+                .padding(8)
+                .width(Length::Fixed(250.0))
+                .on_input(Message::UpdateGpgCommand)))
+        .push(Row::new()
+            .spacing(5)
+            .push(TextInput::new("KeePass database password", transfer.password)
+                .password()
+                .padding(8)
+                .width(Length::Fixed(250.0))
+                .on_input(Message::UpdateTransferPassword))
+            .push(PickList::new(&KdbxCipher::ALL[..], Some(transfer.kdbx_cipher), Message::ChangeKdbxCipher))
+            .push(Button::new("Export KeePass database").on_press(Message::ExportKdbx)))
+        .push(Text::new(transfer.status.to_string()).size(14));
+    if let Some(preview) = transfer.preview {
+        col = col.push(view_import_preview(style, preview, transfer.skip_duplicates));
+    }
+    col
+}
+
+// Entries an import would add, with duplicates flagged and the rows that couldn't be read
+fn view_import_preview(style: StyleType, preview: &ImportPreview, skip_duplicates: bool) -> Column<'static, Message> {
+    let added = preview.entries.len() - if skip_duplicates { preview.duplicates() } else { 0 };
+    let mut col = Column::new()
+        .spacing(5)
+        .push(Text::new(preview.summary(skip_duplicates)).size(14))
+        .push(Checkbox::new("Skip entries already in the vault", skip_duplicates, Message::ToggleSkipDuplicates))
+        .push(Row::new()
+            .spacing(5)
+            .push(Button::new(Text::new(format!("Import {} entries", added))).on_press(Message::ConfirmImport))
+            .push(Button::new("Cancel").on_press(Message::CancelImport)));
+    for imported in preview.entries.iter().take(MAX_PREVIEW_ROWS) {
+        let subtitle = field_text(&imported.entry[imported.entry_type.subtitle_field()]);
+        let mut row = Row::new()
+            .spacing(5)
+            .push(Text::new(format!("{} ({}, {})", field_text(&imported.entry["title"]), imported.entry_type.label(), subtitle)).size(14));
+        if !imported.folder_path.is_empty() {
+            row = row.push(Text::new(format!("in {}", imported.folder_path.join(" / "))).size(14));
+        }
+        if imported.duplicate {
+            row = row.push(item_list::view_badge(style, String::from("Duplicate")));
+        }
+        col = col.push(row);
+    }
+    if preview.entries.len() > MAX_PREVIEW_ROWS {
+        col = col.push(Text::new(format!("and {} more", preview.entries.len() - MAX_PREVIEW_ROWS)).size(14));
+    }
+    if !preview.skipped.is_empty() {
+        col = col.push(Text::new(format!("{} rows couldn't be read:", preview.skipped.len())));
+        for skipped in &preview.skipped {
+            col = col.push(Text::new(format!("{}: {}", skipped.location, skipped.reason)).size(14));
+        }
+    }
+    col
+}
@@ -0,0 +1,76 @@
+use iced::Length;
+use iced::widget::{Column, Container, Text, Button, Row, Scrollable};
+use iced::Element;
+use serde_json::Value;
+
+// Import Message enum from the main application module
+use crate::gui::core::message::Message;
+use crate::gui::pages::details_page::EntryType;
+use crate::gui::pages::item_list::{highlighted_text, view_search_bar};
+use crate::gui::styles::types::{
+    style_type::StyleType,
+    style_tuple::StyleTuple,
+    element_type::ElementType,
+};
+use crate::vault::search::{self, field_text};
+
+// Define the user interface layout for the SearchPage, searching every entry type at once
+pub fn view_page(style: StyleType, vault: &Value, search_query: &str, selected_entry_type: EntryType, selected_entry_id: i32) -> Element<'static, Message> {
+    // Create a text label for the SearchPage
+    let label = Text::new("Search all entries");
+
+    let search_result = |entry_type: EntryType, entry_id: i32, title: Row<'static, Message>, subtitle: Row<'static, Message>| {
+        Button::new(
+            Column::new()
+                .push(Text::new(entry_type.label()).size(14))
+                .push(title)
+                .push(subtitle)
+        )
+            .padding(25)
+            .width(Length::Fill)
+            .style(<StyleTuple as Into<iced::theme::Button>>::into(
+                if entry_type == selected_entry_type && entry_id == selected_entry_id {
+                    StyleTuple(style, ElementType::SelectedItem)
+                } else {
+                    StyleTuple(style, ElementType::ItemListEntry)
+                }
+            ))
+            .on_press(Message::SelectEntryOfType(entry_type, entry_id))
+    };
+
+    let mut col = Column::new()
+        .push(label)
+        .push(view_search_bar(search_query));
+
+    if search_query.trim().is_empty() {
+        col = col.push(Text::new("Type to search passwords, identities and cards"));
+    } else {
+        let results = search::search_all(vault, search_query);
+        if results.is_empty() {
+            col = col.push(Text::new("No matches"));
+        }
+        for found in results {
+            let value = &vault[found.entry_type.as_str()][found.index];
+            let subtitle_field = found.entry_type.subtitle_field();
+            col = col.push(
+                search_result(found.entry_type, found.index as i32,
+                    highlighted_text(style, field_text(&value["title"]), found.highlights.get("title")),
+                    highlighted_text(style, field_text(&value[subtitle_field]), found.highlights.get(subtitle_field)),
+                ),
+            );
+        }
+    }
+
+    let scroll_area = Scrollable::new(col);
+
+    // Create a container to hold the column layout, set its dimensions and position, and return it as an Element
+    Container::new(scroll_area)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .style(<StyleTuple as Into<iced::theme::Container>>::into(
+            StyleTuple(style, ElementType::ItemListColumn),
+        ))
+        .into()
+}
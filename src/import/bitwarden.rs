@@ -0,0 +1,220 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::import::{ImportError, ImportFormat, ImportPreview};
+use crate::vault::card::normalize_card;
+use crate::vault::custom_field::{totp_uri, CustomField, CustomFieldType};
+use crate::vault::date::parse_timestamp;
+use crate::vault::document::{set_documents, DocumentType, IdentityDocument};
+use crate::vault::entry::new_entry;
+use crate::vault::identity::normalize_identity;
+use crate::vault::search::field_text;
+
+// Item types of a Bitwarden export
+const LOGIN: u64 = 1;
+const SECURE_NOTE: u64 = 2;
+const CARD: u64 = 3;
+const IDENTITY: u64 = 4;
+const SSH_KEY: u64 = 5;
+
+// Custom field types, linked fields only point at another field of the item
+const FIELD_TEXT: u64 = 0;
+const FIELD_HIDDEN: u64 = 1;
+const FIELD_BOOLEAN: u64 = 2;
+
+fn text(value: &Value) -> String {
+    field_text(value).trim().to_string()
+}
+
+// Logins, cards, identities, secure notes and SSH keys of an unencrypted Bitwarden JSON export.
+// Folders are recreated by name, Bitwarden nests them with "/" in the name.
+pub fn parse_bitwarden_json(json: &str, folder: Option<u64>) -> Result<ImportPreview, ImportError> {
+    let export: Value = serde_json::from_str(json)?;
+    if export["encrypted"].as_bool().unwrap_or(false) {
+        return Err(ImportError::PasswordProtected);
+    }
+    let items = export["items"].as_array().ok_or(ImportError::UnrecognizedFormat("a Bitwarden JSON export with an items list"))?;
+    let folders: HashMap<String, Vec<String>> = export["folders"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|folder| (text(&folder["id"]), text(&folder["name"]).split('/').map(|name| name.trim().to_string()).collect()))
+        .collect();
+
+    let mut preview = ImportPreview::new(ImportFormat::BitwardenJson);
+    for (i, item) in items.iter().enumerate() {
+        let location = match text(&item["name"]).as_str() {
+            "" => format!("Item {}", i + 1),
+            name => format!("Item {} ({})", i + 1, name),
+        };
+        if !item["deletedDate"].is_null() {
+            preview.skip(location, "In the trash");
+            continue;
+        }
+        let item_type = item["type"].as_u64().unwrap_or(0);
+        let (entry_type, mut entry) = match item_type {
+            LOGIN => (EntryType::Passwords, login(item, folder)),
+            SECURE_NOTE => (EntryType::Notes, secure_note(item, folder)),
+            CARD => (EntryType::Cards, card(item, folder)),
+            IDENTITY => (EntryType::Identities, identity(item, folder)),
+            SSH_KEY => (EntryType::SshKeys, ssh_key(item, folder)),
+            _ => {
+                preview.skip(location, format!("Unsupported item type {}", item_type));
+                continue;
+            },
+        };
+
+        entry["title"] = Value::from(text(&item["name"]));
+        entry["favorite"] = Value::Bool(item["favorite"].as_bool().unwrap_or(false));
+        for (key, date) in [("created", "creationDate"), ("modified", "revisionDate")] {
+            if let Some(timestamp) = item[date].as_str().and_then(parse_timestamp) {
+                entry[key] = Value::from(timestamp);
+            }
+        }
+        let mut custom = custom_fields(item);
+        // Only logins and notes have a notes field of their own
+        let notes = field_text(&item["notes"]);
+        if !notes.trim().is_empty() && !matches!(entry_type, EntryType::Passwords | EntryType::Notes) {
+            custom.push(CustomField { label: String::from("Notes"), field_type: CustomFieldType::Text, value: notes });
+        }
+        if entry_type == EntryType::Passwords {
+            custom.extend(extra_uris(item));
+        }
+        if entry_type == EntryType::Identities {
+            custom.extend(identity_extras(item));
+        }
+        if !custom.is_empty() {
+            entry["custom_fields"] = serde_json::to_value(custom)?;
+        }
+
+        let imported = preview.add(entry_type, entry);
+        if let Some(path) = item["folderId"].as_str().and_then(|id| folders.get(id)) {
+            imported.folder_path = path.clone();
+        }
+    }
+    Ok(preview)
+}
+
+fn login(item: &Value, folder: Option<u64>) -> Value {
+    let login = &item["login"];
+    let mut entry = new_entry(EntryType::Passwords, folder);
+    entry["url"] = Value::from(login["uris"][0]["uri"].as_str().unwrap_or("").trim());
+    entry["username"] = Value::from(text(&login["username"]));
+    entry["password"] = Value::from(field_text(&login["password"]));
+    // Bitwarden stores either an otpauth URI or a bare secret
+    let totp = text(&login["totp"]);
+    if !totp.is_empty() {
+        entry["otpauth"] = Value::from(totp_uri(&totp));
+    }
+    entry["notes"] = Value::from(field_text(&item["notes"]));
+    entry
+}
+
+// A login has one URL field, the other URIs are kept as custom fields
+fn extra_uris(item: &Value) -> Vec<CustomField> {
+    item["login"]["uris"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .skip(1)
+        .enumerate()
+        .map(|(i, uri)| CustomField { label: format!("URL {}", i + 2), field_type: CustomFieldType::Url, value: text(&uri["uri"]) })
+        .filter(|field| !field.value.is_empty())
+        .collect()
+}
+
+fn secure_note(item: &Value, folder: Option<u64>) -> Value {
+    let mut entry = new_entry(EntryType::Notes, folder);
+    entry["body"] = Value::from(field_text(&item["notes"]));
+    entry
+}
+
+fn card(item: &Value, folder: Option<u64>) -> Value {
+    let card = &item["card"];
+    let mut entry = new_entry(EntryType::Cards, folder);
+    entry["name"] = Value::from(text(&card["cardholderName"]));
+    entry["card_number"] = Value::from(text(&card["number"]));
+    entry["security_code"] = Value::from(text(&card["code"]));
+    let (month, year) = (text(&card["expMonth"]), text(&card["expYear"]));
+    if !month.is_empty() && !year.is_empty() {
+        entry["expiration_date"] = Value::from(format!("{}/{}", month, year));
+    }
+    normalize_card(&mut entry);
+    entry
+}
+
+fn identity(item: &Value, folder: Option<u64>) -> Value {
+    let identity = &item["identity"];
+    let mut entry = new_entry(EntryType::Identities, folder);
+    entry["first_name"] = Value::from(text(&identity["firstName"]));
+    entry["middle_initial"] = Value::from(text(&identity["middleName"]).chars().next().map(String::from).unwrap_or_default());
+    entry["last_name"] = Value::from(text(&identity["lastName"]));
+    let address: Vec<String> = ["address1", "address2", "address3"]
+        .iter()
+        .map(|key| text(&identity[*key]))
+        .filter(|line| !line.is_empty())
+        .collect();
+    entry["address"] = Value::from(address.join(", "));
+    entry["city"] = Value::from(text(&identity["city"]));
+    entry["state"] = Value::from(text(&identity["state"]));
+    entry["zipcode"] = Value::from(text(&identity["postalCode"]));
+    entry["country"] = Value::from(text(&identity["country"]));
+    entry["phone"] = Value::from(text(&identity["phone"]));
+    entry["email"] = Value::from(text(&identity["email"]));
+
+    // Document numbers become identity documents, encrypted like any other
+    let documents: Vec<IdentityDocument> = [
+        ("passportNumber", DocumentType::Passport),
+        ("licenseNumber", DocumentType::DriversLicence),
+        ("ssn", DocumentType::NationalId),
+    ]
+        .into_iter()
+        .filter_map(|(key, document_type)| {
+            let number = text(&identity[key]);
+            (!number.is_empty()).then(|| IdentityDocument { number, ..IdentityDocument::new(document_type) })
+        })
+        .collect();
+    if !documents.is_empty() {
+        set_documents(&mut entry, &documents);
+    }
+    normalize_identity(&mut entry);
+    entry
+}
+
+// Identity fields without a place of their own in Keybolt
+fn identity_extras(item: &Value) -> Vec<CustomField> {
+    [("company", "Company"), ("username", "Username")]
+        .into_iter()
+        .map(|(key, label)| CustomField { label: label.to_string(), field_type: CustomFieldType::Text, value: text(&item["identity"][key]) })
+        .filter(|field| !field.value.is_empty())
+        .collect()
+}
+
+fn ssh_key(item: &Value, folder: Option<u64>) -> Value {
+    let key = &item["sshKey"];
+    let mut entry = new_entry(EntryType::SshKeys, folder);
+    entry["private_key"] = Value::from(field_text(&key["privateKey"]));
+    entry["public_key"] = Value::from(text(&key["publicKey"]));
+    entry["fingerprint"] = Value::from(text(&key["keyFingerprint"]));
+    // The comment is the part of the public key line after the key itself
+    entry["comment"] = Value::from(text(&key["publicKey"]).splitn(3, ' ').nth(2).unwrap_or(""));
+    entry
+}
+
+// Custom fields of the item, linked fields have no value of their own and are left out
+fn custom_fields(item: &Value) -> Vec<CustomField> {
+    item["fields"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|field| {
+            let field_type = match field["type"].as_u64() {
+                Some(FIELD_TEXT) | Some(FIELD_BOOLEAN) => CustomFieldType::Text,
+                Some(FIELD_HIDDEN) => CustomFieldType::Hidden,
+                _ => return None,
+            };
+            Some(CustomField { label: text(&field["name"]), field_type, value: field_text(&field["value"]) })
+        })
+        .collect()
+}
@@ -0,0 +1,58 @@
+use serde_json::Value;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::import::csv::{column_index, parse_csv};
+use crate::import::{url_host, ImportError, ImportFormat, ImportPreview};
+use crate::vault::entry::new_entry;
+
+// Chrome exports name,url,username,password,note. Firefox exports url,username,password
+// followed by httpRealm, formActionOrigin, guid and timestamps in milliseconds.
+pub fn parse_browser_csv(text: &str, folder: Option<u64>) -> Result<ImportPreview, ImportError> {
+    let records = parse_csv(text).map_err(ImportError::UnterminatedQuote)?;
+    let mut records = records.into_iter();
+    let header = records.next().ok_or(ImportError::UnrecognizedFormat("a header row with url, username and password columns"))?;
+    let column = |names: &[&str]| column_index(&header.fields, names);
+    let (Some(url), Some(username), Some(password)) = (column(&["url", "origin"]), column(&["username", "login"]), column(&["password"])) else {
+        return Err(ImportError::UnrecognizedFormat("a header row with url, username and password columns"));
+    };
+    let name = column(&["name", "title"]);
+    let note = column(&["note", "notes"]);
+    let otpauth = column(&["otpauth"]);
+    let created = column(&["timecreated"]);
+    let modified = column(&["timepasswordchanged"]);
+    let last_used = column(&["timelastused"]);
+
+    let mut preview = ImportPreview::new(ImportFormat::BrowserCsv);
+    for record in records {
+        if record.fields.len() != header.fields.len() {
+            preview.skip(format!("Line {}", record.line), format!("Expected {} columns, found {}", header.fields.len(), record.fields.len()));
+            continue;
+        }
+        let (url, username, password) = (record.field(Some(url)), record.field(Some(username)), record.raw(Some(password)));
+        if username.is_empty() && password.is_empty() {
+            preview.skip(format!("Line {}", record.line), "No username or password");
+            continue;
+        }
+
+        let mut entry = new_entry(EntryType::Passwords, folder);
+        // Chrome names logins after the site, Firefox leaves the title to us
+        let title = match record.field(name) {
+            name if !name.is_empty() => name,
+            _ if !url.is_empty() => url_host(&url),
+            _ => username.clone(),
+        };
+        entry["title"] = Value::from(title);
+        entry["url"] = Value::from(url);
+        entry["username"] = Value::from(username);
+        entry["password"] = Value::from(password);
+        entry["notes"] = Value::from(record.raw(note));
+        entry["otpauth"] = Value::from(record.field(otpauth));
+        for (key, index) in [("created", created), ("modified", modified), ("last_used", last_used)] {
+            if let Ok(millis) = record.field(index).parse::<u64>() {
+                entry[key] = Value::from(millis / 1000);
+            }
+        }
+        preview.add(EntryType::Passwords, entry);
+    }
+    Ok(preview)
+}
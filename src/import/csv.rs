@@ -0,0 +1,79 @@
+/// A CSV record with the line it starts on, quoted fields can span several lines
+pub struct CsvRecord {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+impl CsvRecord {
+    // Value of a column as exported, passwords and notes keep surrounding spaces as part of them
+    pub fn raw(&self, column: Option<usize>) -> String {
+        column.and_then(|i| self.fields.get(i)).cloned().unwrap_or_default()
+    }
+
+    // Value of a column without surrounding spaces, for URLs, names and the like
+    pub fn field(&self, column: Option<usize>) -> String {
+        self.raw(column).trim().to_string()
+    }
+}
+
+// RFC 4180 parsing: fields are separated by commas, quoted fields may contain commas,
+// line breaks and doubled quotes. Fails with the line of a quote that is never closed.
+pub fn parse_csv(text: &str) -> Result<Vec<CsvRecord>, usize> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut quote_line = 0;
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            },
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => {
+                in_quotes = true;
+                quote_line = line;
+            },
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {},
+            ('\n', false) | ('\r', false) => {
+                fields.push(std::mem::take(&mut field));
+                // Blank lines between records are ignored
+                if fields.len() > 1 || !fields[0].is_empty() {
+                    records.push(CsvRecord { line: record_line, fields: std::mem::take(&mut fields) });
+                }
+                fields.clear();
+                line += 1;
+                record_line = line;
+            },
+            (c, _) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            },
+        }
+    }
+    if in_quotes {
+        return Err(quote_line);
+    }
+    fields.push(field);
+    if fields.len() > 1 || !fields[0].is_empty() {
+        records.push(CsvRecord { line: record_line, fields });
+    }
+    Ok(records)
+}
+
+// Position of the column going by one of the given names, ignoring case and spaces
+pub fn column_index(header: &[String], names: &[&str]) -> Option<usize> {
+    header.iter().position(|column| {
+        let column = column.trim().to_ascii_lowercase().replace(' ', "");
+        names.contains(&column.as_str())
+    })
+}
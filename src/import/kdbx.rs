@@ -0,0 +1,580 @@
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecryptMut, BlockEncrypt, BlockEncryptMut, KeyInit, KeyIvInit, StreamCipher};
+use aes::Aes256;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose;
+use base64::Engine;
+use chacha20::ChaCha20;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use salsa20::Salsa20;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Write};
+
+// File signature and the version written, 4.0
+const SIGNATURE_1: u32 = 0x9AA2_D903;
+const SIGNATURE_2: u32 = 0xB54B_FB67;
+const VERSION_4_0: u32 = 0x0004_0000;
+
+// Outer header fields
+const HEADER_END: u8 = 0;
+const HEADER_CIPHER_ID: u8 = 2;
+const HEADER_COMPRESSION: u8 = 3;
+const HEADER_MASTER_SEED: u8 = 4;
+const HEADER_ENCRYPTION_IV: u8 = 7;
+const HEADER_KDF_PARAMETERS: u8 = 11;
+
+// Inner header fields, found at the start of the decrypted payload
+const INNER_END: u8 = 0;
+const INNER_STREAM_ID: u8 = 1;
+const INNER_STREAM_KEY: u8 = 2;
+const INNER_BINARY: u8 = 3;
+
+// Protected values are encrypted with one of these streams
+const STREAM_SALSA20: u32 = 2;
+const STREAM_CHACHA20: u32 = 3;
+const SALSA20_NONCE: [u8; 8] = [0xE8, 0x30, 0x09, 0x4B, 0x97, 0x20, 0x5D, 0x2A];
+
+const CIPHER_AES256: [u8; 16] = [0x31, 0xC1, 0xF2, 0xE6, 0xBF, 0x71, 0x43, 0x50, 0xBE, 0x58, 0x05, 0x21, 0x6A, 0xFC, 0x5A, 0xFF];
+const CIPHER_CHACHA20: [u8; 16] = [0xD6, 0x03, 0x8A, 0x2B, 0x8B, 0x6F, 0x4C, 0xB5, 0xA5, 0x24, 0x33, 0x9A, 0x31, 0xDB, 0xB5, 0x9A];
+const KDF_ARGON2D: [u8; 16] = [0xEF, 0x63, 0x6D, 0xDF, 0x8C, 0x29, 0x44, 0x4B, 0x91, 0xF7, 0xA9, 0xA4, 0x03, 0xE3, 0x0A, 0x0C];
+const KDF_ARGON2ID: [u8; 16] = [0x9E, 0x29, 0x8B, 0x19, 0x56, 0xDB, 0x47, 0x73, 0xB2, 0x3D, 0xFC, 0x3E, 0xC6, 0xF0, 0xA1, 0xE6];
+const KDF_AES: [u8; 16] = [0xC9, 0xD9, 0xF3, 0x9A, 0x62, 0x8A, 0x44, 0x60, 0xBF, 0x74, 0x0D, 0x08, 0xC1, 0x8A, 0x4F, 0xEA];
+
+// Value types of a KDF parameter dictionary
+const VARIANT_VERSION: u16 = 0x0100;
+const VARIANT_END: u8 = 0x00;
+const VARIANT_UINT32: u8 = 0x04;
+const VARIANT_UINT64: u8 = 0x05;
+const VARIANT_BYTES: u8 = 0x42;
+
+// The payload is written in HMAC protected blocks of this size
+const BLOCK_SIZE: usize = 1024 * 1024;
+// Block index the header HMAC is keyed with
+const HEADER_BLOCK_INDEX: u64 = u64::MAX;
+
+// Argon2id settings of exported databases, close to what KeePassXC picks on a laptop
+const EXPORT_MEMORY: u64 = 64 * 1024 * 1024;
+const EXPORT_ITERATIONS: u64 = 10;
+const EXPORT_PARALLELISM: u32 = 2;
+
+// Highest KDF settings a database may ask for, KeePassXC's own settings stay below them.
+// The header isn't authenticated before the key is derived, so larger values are refused.
+const MAX_ARGON2_MEMORY: u64 = 1024 * 1024 * 1024;
+const MAX_ARGON2_ITERATIONS: u64 = 1000;
+const MAX_ARGON2_PARALLELISM: u32 = 64;
+const MAX_AES_ROUNDS: u64 = 100_000_000;
+
+/// Cipher the payload of a KDBX file is encrypted with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KdbxCipher {
+    Aes256,
+    ChaCha20,
+}
+
+impl KdbxCipher {
+    pub const ALL: [KdbxCipher; 2] = [KdbxCipher::Aes256, KdbxCipher::ChaCha20];
+
+    fn uuid(&self) -> [u8; 16] {
+        match self {
+            KdbxCipher::Aes256 => CIPHER_AES256,
+            KdbxCipher::ChaCha20 => CIPHER_CHACHA20,
+        }
+    }
+
+    fn from_uuid(uuid: &[u8]) -> Option<KdbxCipher> {
+        KdbxCipher::ALL.into_iter().find(|cipher| cipher.uuid() == uuid)
+    }
+
+    fn iv_len(&self) -> usize {
+        match self {
+            KdbxCipher::Aes256 => 16,
+            KdbxCipher::ChaCha20 => 12,
+        }
+    }
+
+    fn encrypt(&self, key: &[u8], iv: &[u8], mut data: Vec<u8>) -> Result<Vec<u8>, KdbxError> {
+        match self {
+            KdbxCipher::Aes256 => Ok(cbc::Encryptor::<Aes256>::new_from_slices(key, iv)
+                .map_err(|_| KdbxError::Corrupt("encryption IV"))?
+                .encrypt_padded_vec_mut::<Pkcs7>(&data)),
+            KdbxCipher::ChaCha20 => {
+                ChaCha20::new_from_slices(key, iv).map_err(|_| KdbxError::Corrupt("encryption IV"))?.apply_keystream(&mut data);
+                Ok(data)
+            },
+        }
+    }
+
+    // Padding that doesn't check out means the key was right but the payload is damaged
+    fn decrypt(&self, key: &[u8], iv: &[u8], mut data: Vec<u8>) -> Result<Vec<u8>, KdbxError> {
+        match self {
+            KdbxCipher::Aes256 => cbc::Decryptor::<Aes256>::new_from_slices(key, iv)
+                .map_err(|_| KdbxError::Corrupt("encryption IV"))?
+                .decrypt_padded_vec_mut::<Pkcs7>(&data)
+                .map_err(|_| KdbxError::Corrupt("encrypted payload")),
+            KdbxCipher::ChaCha20 => {
+                ChaCha20::new_from_slices(key, iv).map_err(|_| KdbxError::Corrupt("encryption IV"))?.apply_keystream(&mut data);
+                Ok(data)
+            },
+        }
+    }
+}
+
+impl fmt::Display for KdbxCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            KdbxCipher::Aes256 => "AES-256",
+            KdbxCipher::ChaCha20 => "ChaCha20",
+        })
+    }
+}
+
+/// How the password is turned into the database key, as stored in the header
+#[derive(Clone, Debug)]
+pub enum Kdf {
+    Argon2 { algorithm: Algorithm, salt: Vec<u8>, memory: u64, iterations: u64, parallelism: u32, version: u32 },
+    /// The older AES-KDF, rounds of AES-256 over the key
+    Aes { seed: Vec<u8>, rounds: u64 },
+}
+
+impl Kdf {
+    // Argon2id with a fresh salt, used for exports
+    pub fn argon2id() -> Kdf {
+        let mut salt = vec![0; 32];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Kdf::Argon2 {
+            algorithm: Algorithm::Argon2id,
+            salt,
+            memory: EXPORT_MEMORY,
+            iterations: EXPORT_ITERATIONS,
+            parallelism: EXPORT_PARALLELISM,
+            version: 0x13,
+        }
+    }
+
+    fn parse(data: &[u8]) -> Result<Kdf, KdbxError> {
+        let params = parse_variant_dictionary(data)?;
+        let bytes = |key: &str| params.get(key).cloned().ok_or(KdbxError::Corrupt("KDF parameters"));
+        let number = |key: &str| match params.get(key).map(|value| value.as_slice()) {
+            Some(&[a, b, c, d]) => Ok(u32::from_le_bytes([a, b, c, d]) as u64),
+            Some(value) if value.len() == 8 => Ok(u64::from_le_bytes(value.try_into().expect("length checked"))),
+            _ => Err(KdbxError::Corrupt("KDF parameters")),
+        };
+        let uuid = bytes("$UUID")?;
+        let algorithm = match uuid.as_slice() {
+            uuid if uuid == KDF_AES => return Ok(Kdf::Aes { seed: bytes("S")?, rounds: number("R")? }),
+            uuid if uuid == KDF_ARGON2D => Algorithm::Argon2d,
+            uuid if uuid == KDF_ARGON2ID => Algorithm::Argon2id,
+            _ => return Err(KdbxError::UnsupportedKdf),
+        };
+        Ok(Kdf::Argon2 {
+            algorithm,
+            salt: bytes("S")?,
+            memory: number("M")?,
+            iterations: number("I")?,
+            parallelism: number("P")? as u32,
+            version: number("V")? as u32,
+        })
+    }
+
+    fn to_dictionary(&self) -> Vec<u8> {
+        let mut items: Vec<(u8, &str, Vec<u8>)> = Vec::new();
+        match self {
+            Kdf::Argon2 { algorithm, salt, memory, iterations, parallelism, version } => {
+                let uuid = if *algorithm == Algorithm::Argon2d { KDF_ARGON2D } else { KDF_ARGON2ID };
+                items.push((VARIANT_BYTES, "$UUID", uuid.to_vec()));
+                items.push((VARIANT_BYTES, "S", salt.clone()));
+                items.push((VARIANT_UINT64, "M", memory.to_le_bytes().to_vec()));
+                items.push((VARIANT_UINT64, "I", iterations.to_le_bytes().to_vec()));
+                items.push((VARIANT_UINT32, "P", parallelism.to_le_bytes().to_vec()));
+                items.push((VARIANT_UINT32, "V", version.to_le_bytes().to_vec()));
+            },
+            Kdf::Aes { seed, rounds } => {
+                items.push((VARIANT_BYTES, "$UUID", KDF_AES.to_vec()));
+                items.push((VARIANT_BYTES, "S", seed.clone()));
+                items.push((VARIANT_UINT64, "R", rounds.to_le_bytes().to_vec()));
+            },
+        }
+        let mut data = VARIANT_VERSION.to_le_bytes().to_vec();
+        for (value_type, name, value) in items {
+            data.push(value_type);
+            data.extend((name.len() as u32).to_le_bytes());
+            data.extend(name.as_bytes());
+            data.extend((value.len() as u32).to_le_bytes());
+            data.extend(value);
+        }
+        data.push(VARIANT_END);
+        data
+    }
+
+    // The transformed key, from SHA-256(SHA-256(password)) as no key file is used
+    fn transform(&self, password: &str) -> Result<[u8; 32], KdbxError> {
+        let composite = Sha256::digest(Sha256::digest(password.as_bytes()));
+        let mut key = [0; 32];
+        match self {
+            Kdf::Argon2 { algorithm, salt, memory, iterations, parallelism, version } => {
+                if *memory > MAX_ARGON2_MEMORY || *iterations > MAX_ARGON2_ITERATIONS || *parallelism > MAX_ARGON2_PARALLELISM {
+                    return Err(KdbxError::Corrupt("KDF parameters"));
+                }
+                let version = if *version == 0x10 { Version::V0x10 } else { Version::V0x13 };
+                let params = Params::new((memory / 1024) as u32, *iterations as u32, *parallelism, Some(32))
+                    .map_err(|_| KdbxError::Corrupt("KDF parameters"))?;
+                Argon2::new(*algorithm, version, params)
+                    .hash_password_into(&composite, salt, &mut key)
+                    .map_err(|_| KdbxError::Corrupt("KDF parameters"))?;
+            },
+            Kdf::Aes { seed, rounds } => {
+                if *rounds > MAX_AES_ROUNDS {
+                    return Err(KdbxError::Corrupt("KDF parameters"));
+                }
+                let cipher = Aes256::new_from_slice(seed).map_err(|_| KdbxError::Corrupt("KDF parameters"))?;
+                let mut blocks = [GenericArray::clone_from_slice(&composite[..16]), GenericArray::clone_from_slice(&composite[16..])];
+                for _ in 0..*rounds {
+                    cipher.encrypt_blocks(&mut blocks);
+                }
+                key.copy_from_slice(&Sha256::new().chain_update(blocks[0]).chain_update(blocks[1]).finalize());
+            },
+        }
+        Ok(key)
+    }
+}
+
+/// Keystream protected values in the XML are XORed with, in document order
+pub enum InnerStream {
+    ChaCha20(ChaCha20),
+    Salsa20(Salsa20),
+}
+
+impl InnerStream {
+    fn new(id: u32, key: &[u8]) -> Result<InnerStream, KdbxError> {
+        match id {
+            STREAM_CHACHA20 => {
+                let hash = Sha512::digest(key);
+                Ok(InnerStream::ChaCha20(ChaCha20::new_from_slices(&hash[..32], &hash[32..44]).expect("slices have the key and nonce lengths")))
+            },
+            STREAM_SALSA20 => {
+                let hash = Sha256::digest(key);
+                Ok(InnerStream::Salsa20(Salsa20::new_from_slices(&hash, &SALSA20_NONCE).expect("slices have the key and nonce lengths")))
+            },
+            _ => Err(KdbxError::Corrupt("inner stream")),
+        }
+    }
+
+    // ChaCha20 stream with a fresh key, for writing a database
+    pub fn generate() -> (InnerStream, Vec<u8>) {
+        let mut key = vec![0; 64];
+        rand::thread_rng().fill_bytes(&mut key);
+        let stream = InnerStream::new(STREAM_CHACHA20, &key).expect("ChaCha20 is a known stream");
+        (stream, key)
+    }
+
+    fn apply(&mut self, data: &mut [u8]) {
+        match self {
+            InnerStream::ChaCha20(cipher) => cipher.apply_keystream(data),
+            InnerStream::Salsa20(cipher) => cipher.apply_keystream(data),
+        }
+    }
+
+    // Plain text of a protected value, each value has to be unprotected in turn
+    pub fn unprotect(&mut self, value: &str) -> String {
+        let mut data = general_purpose::STANDARD.decode(value.trim()).unwrap_or_default();
+        self.apply(&mut data);
+        String::from_utf8_lossy(&data).to_string()
+    }
+
+    pub fn protect(&mut self, value: &str) -> String {
+        let mut data = value.as_bytes().to_vec();
+        self.apply(&mut data);
+        general_purpose::STANDARD.encode(data)
+    }
+}
+
+/// Decrypted content of a KDBX file
+pub struct KdbxContent {
+    pub xml: String,
+    /// Attachments, entries refer to them by index
+    pub binaries: Vec<Vec<u8>>,
+    /// Unprotects the XML's protected values
+    pub inner_stream: InnerStream,
+}
+
+// Little endian fields of the file, failing once the data runs out
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], KdbxError> {
+        let taken = self.data.get(self.pos..self.pos.checked_add(len).ok_or(KdbxError::Truncated)?).ok_or(KdbxError::Truncated)?;
+        self.pos += len;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, KdbxError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, KdbxError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().expect("two bytes taken")))
+    }
+
+    fn u32(&mut self) -> Result<u32, KdbxError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("four bytes taken")))
+    }
+
+    // A header field: its id and value
+    fn field(&mut self) -> Result<(u8, &'a [u8]), KdbxError> {
+        let id = self.u8()?;
+        let len = self.u32()? as usize;
+        Ok((id, self.take(len)?))
+    }
+}
+
+// Raw values by name, the KDF knows how to read each of its parameters
+fn parse_variant_dictionary(data: &[u8]) -> Result<HashMap<String, Vec<u8>>, KdbxError> {
+    let mut reader = Reader { data, pos: 0 };
+    if reader.u16()? & 0xFF00 != VARIANT_VERSION {
+        return Err(KdbxError::Corrupt("KDF parameters"));
+    }
+    let mut values = HashMap::new();
+    while reader.u8()? != VARIANT_END {
+        let name_len = reader.u32()? as usize;
+        let name = String::from_utf8_lossy(reader.take(name_len)?).to_string();
+        let value_len = reader.u32()? as usize;
+        values.insert(name, reader.take(value_len)?.to_vec());
+    }
+    Ok(values)
+}
+
+fn write_field(data: &mut Vec<u8>, id: u8, value: &[u8]) {
+    data.push(id);
+    data.extend((value.len() as u32).to_le_bytes());
+    data.extend(value);
+}
+
+// HMAC of a block, keyed with the block's index so blocks can't be reordered
+fn block_mac(hmac_key: &[u8], index: u64) -> Hmac<Sha256> {
+    let key = Sha512::new().chain_update(index.to_le_bytes()).chain_update(hmac_key).finalize();
+    <Hmac<Sha256> as Mac>::new_from_slice(&key).expect("HMAC accepts any key length")
+}
+
+// Keys for the payload cipher and for the HMACs
+fn payload_keys(master_seed: &[u8], transformed: &[u8; 32]) -> ([u8; 32], [u8; 64]) {
+    let cipher_key = Sha256::new().chain_update(master_seed).chain_update(transformed).finalize().into();
+    let hmac_key = Sha512::new().chain_update(master_seed).chain_update(transformed).chain_update([1]).finalize().into();
+    (cipher_key, hmac_key)
+}
+
+// Decrypt a KDBX 4 file protected by a password, key files aren't supported
+pub fn read_kdbx(data: &[u8], password: &str) -> Result<KdbxContent, KdbxError> {
+    let mut reader = Reader { data, pos: 0 };
+    if reader.u32()? != SIGNATURE_1 || reader.u32()? != SIGNATURE_2 {
+        return Err(KdbxError::NotKdbx);
+    }
+    let version = reader.u32()?;
+    if version >> 16 != 4 {
+        return Err(KdbxError::UnsupportedVersion(version >> 16));
+    }
+
+    let (mut cipher, mut compressed, mut master_seed, mut iv, mut kdf) = (None, false, None, None, None);
+    loop {
+        let (id, value) = reader.field()?;
+        match id {
+            HEADER_END => break,
+            HEADER_CIPHER_ID => cipher = Some(KdbxCipher::from_uuid(value).ok_or(KdbxError::UnsupportedCipher)?),
+            HEADER_COMPRESSION => compressed = value.first() == Some(&1),
+            HEADER_MASTER_SEED => master_seed = Some(value),
+            HEADER_ENCRYPTION_IV => iv = Some(value),
+            HEADER_KDF_PARAMETERS => kdf = Some(Kdf::parse(value)?),
+            _ => {},
+        }
+    }
+    let (Some(cipher), Some(master_seed), Some(iv), Some(kdf)) = (cipher, master_seed, iv, kdf) else {
+        return Err(KdbxError::Corrupt("header"));
+    };
+    let header = &data[..reader.pos];
+    if Sha256::digest(header).as_slice() != reader.take(32)? {
+        return Err(KdbxError::Corrupt("header checksum"));
+    }
+
+    // The header HMAC is the first thing the key is needed for, a mismatch means a wrong password
+    let transformed = kdf.transform(password)?;
+    let (cipher_key, hmac_key) = payload_keys(master_seed, &transformed);
+    let mut mac = block_mac(&hmac_key, HEADER_BLOCK_INDEX);
+    mac.update(header);
+    mac.verify_slice(reader.take(32)?).map_err(|_| KdbxError::WrongPassword)?;
+
+    let mut payload = Vec::new();
+    for index in 0.. {
+        let expected = reader.take(32)?;
+        let len = reader.take(4)?;
+        let block = reader.take(u32::from_le_bytes(len.try_into().expect("four bytes taken")) as usize)?;
+        let mut mac = block_mac(&hmac_key, index);
+        mac.update(&index.to_le_bytes());
+        mac.update(len);
+        mac.update(block);
+        mac.verify_slice(expected).map_err(|_| KdbxError::Corrupt("block checksum"))?;
+        if block.is_empty() {
+            break;
+        }
+        payload.extend_from_slice(block);
+    }
+
+    let mut payload = cipher.decrypt(&cipher_key, iv, payload)?;
+    if compressed {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(payload.as_slice()).read_to_end(&mut decompressed).map_err(|_| KdbxError::Corrupt("compressed payload"))?;
+        payload = decompressed;
+    }
+
+    let mut reader = Reader { data: &payload, pos: 0 };
+    let (mut stream_id, mut stream_key, mut binaries) = (None, None, Vec::new());
+    loop {
+        let (id, value) = reader.field()?;
+        match id {
+            INNER_END => break,
+            INNER_STREAM_ID => stream_id = value.try_into().ok().map(u32::from_le_bytes),
+            INNER_STREAM_KEY => stream_key = Some(value),
+            // The first byte only flags whether the binary is kept protected in memory
+            INNER_BINARY => binaries.push(value.get(1..).unwrap_or_default().to_vec()),
+            _ => {},
+        }
+    }
+    let (Some(stream_id), Some(stream_key)) = (stream_id, stream_key) else {
+        return Err(KdbxError::Corrupt("inner header"));
+    };
+    let inner_stream = InnerStream::new(stream_id, stream_key)?;
+    let xml = String::from_utf8(payload[reader.pos..].to_vec()).map_err(|_| KdbxError::Corrupt("XML encoding"))?;
+    Ok(KdbxContent { xml, binaries, inner_stream })
+}
+
+// Encrypt a KDBX 4.0 file, the XML's protected values must already be XORed with the
+// inner stream generated alongside `stream_key`
+pub fn write_kdbx(password: &str, cipher: KdbxCipher, kdf: &Kdf, stream_key: &[u8], xml: &str, binaries: &[Vec<u8>]) -> Result<Vec<u8>, KdbxError> {
+    let mut master_seed = [0; 32];
+    rand::thread_rng().fill_bytes(&mut master_seed);
+    let mut iv = vec![0; cipher.iv_len()];
+    rand::thread_rng().fill_bytes(&mut iv);
+
+    let mut data = Vec::new();
+    data.extend(SIGNATURE_1.to_le_bytes());
+    data.extend(SIGNATURE_2.to_le_bytes());
+    data.extend(VERSION_4_0.to_le_bytes());
+    write_field(&mut data, HEADER_CIPHER_ID, &cipher.uuid());
+    write_field(&mut data, HEADER_COMPRESSION, &1u32.to_le_bytes());
+    write_field(&mut data, HEADER_MASTER_SEED, &master_seed);
+    write_field(&mut data, HEADER_ENCRYPTION_IV, &iv);
+    write_field(&mut data, HEADER_KDF_PARAMETERS, &kdf.to_dictionary());
+    write_field(&mut data, HEADER_END, b"\r\n\r\n");
+
+    let transformed = kdf.transform(password)?;
+    let (cipher_key, hmac_key) = payload_keys(&master_seed, &transformed);
+    let checksum = Sha256::digest(&data);
+    let mut mac = block_mac(&hmac_key, HEADER_BLOCK_INDEX);
+    mac.update(&data);
+    data.extend(checksum);
+    data.extend(mac.finalize().into_bytes());
+
+    let mut inner = Vec::new();
+    write_field(&mut inner, INNER_STREAM_ID, &STREAM_CHACHA20.to_le_bytes());
+    write_field(&mut inner, INNER_STREAM_KEY, stream_key);
+    for binary in binaries {
+        write_field(&mut inner, INNER_BINARY, &[&[0], binary.as_slice()].concat());
+    }
+    write_field(&mut inner, INNER_END, &[]);
+    inner.extend(xml.as_bytes());
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&inner).map_err(|_| KdbxError::Corrupt("compressed payload"))?;
+    let compressed = encoder.finish().map_err(|_| KdbxError::Corrupt("compressed payload"))?;
+    let payload = cipher.encrypt(&cipher_key, &iv, compressed)?;
+
+    // The last block is empty and marks the end of the payload
+    let blocks = payload.chunks(BLOCK_SIZE).chain(std::iter::once(&[][..]));
+    for (index, block) in (0u64..).zip(blocks) {
+        let len = (block.len() as u32).to_le_bytes();
+        let mut mac = block_mac(&hmac_key, index);
+        mac.update(&index.to_le_bytes());
+        mac.update(&len);
+        mac.update(block);
+        data.extend(mac.finalize().into_bytes());
+        data.extend(len);
+        data.extend(block);
+    }
+    Ok(data)
+}
+
+// Errors raised when reading or writing a KDBX file
+#[derive(Debug)]
+pub enum KdbxError {
+    NotKdbx,
+    /// Major version of the file, only KDBX 4 is read
+    UnsupportedVersion(u32),
+    UnsupportedCipher,
+    UnsupportedKdf,
+    Truncated,
+    /// The part of the file that doesn't check out
+    Corrupt(&'static str),
+    WrongPassword,
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for KdbxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KdbxError::NotKdbx => write!(f, "Not a KeePass database"),
+            KdbxError::UnsupportedVersion(version) => write!(f, "KDBX {} databases aren't supported, save the database as KDBX 4 first", version),
+            KdbxError::UnsupportedCipher => write!(f, "Only AES-256 and ChaCha20 encrypted databases are supported"),
+            KdbxError::UnsupportedKdf => write!(f, "Only Argon2 and AES-KDF key derivation is supported"),
+            KdbxError::Truncated => write!(f, "The database file is incomplete"),
+            KdbxError::Corrupt(part) => write!(f, "The database is damaged, its {} doesn't check out", part),
+            KdbxError::WrongPassword => write!(f, "Wrong database password, or the database also needs a key file"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cheap key derivations, the export defaults take seconds per database
+    fn cheap_argon2() -> Kdf {
+        Kdf::Argon2 { algorithm: Algorithm::Argon2id, salt: vec![1; 32], memory: 64 * 1024, iterations: 1, parallelism: 1, version: 0x13 }
+    }
+
+    fn cheap_aes() -> Kdf {
+        Kdf::Aes { seed: vec![2; 32], rounds: 10 }
+    }
+
+    #[test]
+    fn round_trip() {
+        for (cipher, kdf) in [(KdbxCipher::Aes256, cheap_argon2()), (KdbxCipher::ChaCha20, cheap_aes())] {
+            let (mut stream, stream_key) = InnerStream::generate();
+            let protected = stream.protect("s3cret <&>");
+            let xml = format!("<KeePassFile><Value Protected=\"True\">{}</Value></KeePassFile>", protected);
+            let binaries = vec![b"attached file".to_vec(), vec![0; 3 * BLOCK_SIZE / 2]];
+            let data = write_kdbx("pw", cipher, &kdf, &stream_key, &xml, &binaries).unwrap();
+
+            let mut content = read_kdbx(&data, "pw").unwrap();
+            assert_eq!(content.xml, xml);
+            assert_eq!(content.binaries, binaries);
+            assert_eq!(content.inner_stream.unprotect(&protected), "s3cret <&>");
+            assert!(matches!(read_kdbx(&data, "wrong"), Err(KdbxError::WrongPassword)));
+        }
+    }
+
+    #[test]
+    fn excessive_kdf_parameters() {
+        let aes = Kdf::Aes { seed: vec![2; 32], rounds: u64::MAX };
+        assert!(matches!(aes.transform("pw"), Err(KdbxError::Corrupt(_))));
+        let argon2 = Kdf::Argon2 { algorithm: Algorithm::Argon2id, salt: vec![1; 32], memory: u64::MAX, iterations: 1, parallelism: 1, version: 0x13 };
+        assert!(matches!(argon2.transform("pw"), Err(KdbxError::Corrupt(_))));
+    }
+}
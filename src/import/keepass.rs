@@ -0,0 +1,622 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use rand::RngCore;
+use roxmltree::{Document, Node, NodeId};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::import::kdbx::{read_kdbx, write_kdbx, InnerStream, Kdf, KdbxCipher};
+use crate::import::{previous_password, previous_password_date, ImportError, ImportFormat, ImportPreview};
+use crate::secure::attachment::{attachments, read_attachment, AttachmentError, MAX_ATTACHMENT_SIZE, MAX_ENTRY_ATTACHMENTS_SIZE};
+use crate::secure::encrypt::decrypt_sensitive_fields;
+use crate::vault::card::normalize_card;
+use crate::vault::custom_field::{custom_fields, set_custom_fields, totp_uri, CustomField, CustomFieldType};
+use crate::vault::date::parse_timestamp;
+use crate::vault::document::{documents, set_documents, IdentityDocument};
+use crate::vault::entry::{entry_tags, is_favorite, new_entry, timestamp};
+use crate::vault::folder::{entry_folder, folders};
+use crate::vault::identity::normalize_identity;
+use crate::vault::schema::{self, FieldKind};
+use crate::vault::search::field_text;
+
+// Seconds from 0001-01-01, where KDBX 4 times count from, to the unix epoch
+const EPOCH_OFFSET: i64 = 62_135_596_800;
+
+// Entry custom data keeping what KeePass has no place for, so exports import back unchanged
+const TYPE_KEY: &str = "Keybolt/Type";
+const FAVORITE_KEY: &str = "Keybolt/Favorite";
+const DOCUMENTS_KEY: &str = "Keybolt/Documents";
+
+// KeePassXC keeps the TOTP of an entry as an otpauth URI in this string, KeePass 2 plugins as a bare secret
+const OTP_KEYS: [&str; 2] = ["otp", "TOTP Seed"];
+
+// Name of the root group of exported databases
+const DATABASE_NAME: &str = "Keybolt";
+const FOLDER_ICON: &str = "48";
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_text(node: Node, name: &str) -> String {
+    child(node, name).and_then(|child| child.text()).unwrap_or("").to_string()
+}
+
+// KDBX 4 stores times as base64 seconds since 0001-01-01, older databases as ISO 8601
+fn parse_time(value: &str) -> Option<u64> {
+    if value.contains('-') {
+        return parse_timestamp(value);
+    }
+    let seconds: [u8; 8] = general_purpose::STANDARD.decode(value.trim()).ok()?.try_into().ok()?;
+    u64::try_from(i64::from_le_bytes(seconds) - EPOCH_OFFSET).ok()
+}
+
+fn format_time(unix_time: u64) -> String {
+    general_purpose::STANDARD.encode((unix_time as i64 + EPOCH_OFFSET).to_le_bytes())
+}
+
+fn new_uuid() -> String {
+    let mut uuid = [0; 16];
+    rand::thread_rng().fill_bytes(&mut uuid);
+    general_purpose::STANDARD.encode(uuid)
+}
+
+/// A string field of a KeePass entry, standard ones like "UserName" included
+struct EntryString {
+    key: String,
+    value: String,
+    protected: bool,
+}
+
+// Walks the group tree of a decrypted database, collecting entries into the preview
+struct DatabaseReader<'a> {
+    /// Plain text of the protected values, which have to be unprotected in document order
+    protected: HashMap<NodeId, String>,
+    binaries: &'a [Vec<u8>],
+    recycle_bin: Option<String>,
+    folder: Option<u64>,
+    preview: ImportPreview,
+}
+
+impl DatabaseReader<'_> {
+    fn strings(&self, entry: Node) -> Vec<EntryString> {
+        entry
+            .children()
+            .filter(|node| node.has_tag_name("String"))
+            .map(|string| {
+                let value = child(string, "Value");
+                let protected = value.and_then(|value| self.protected.get(&value.id()));
+                EntryString {
+                    key: child_text(string, "Key"),
+                    value: protected.cloned().or_else(|| value.and_then(|value| value.text()).map(String::from)).unwrap_or_default(),
+                    protected: protected.is_some(),
+                }
+            })
+            .collect()
+    }
+
+    fn read_group(&mut self, group: Node, path: &[String]) {
+        for node in group.children().filter(|node| node.is_element()) {
+            match node.tag_name().name() {
+                "Entry" => self.read_entry(node, path),
+                "Group" if self.recycle_bin.as_deref() == Some(child_text(node, "UUID").as_str()) => {
+                    let deleted = node.descendants().filter(|entry| entry.has_tag_name("Entry") && entry.parent().is_some_and(|parent| parent.has_tag_name("Group")));
+                    for entry in deleted {
+                        let title = self.strings(entry).into_iter().find(|string| string.key == "Title").map(|string| string.value).unwrap_or_default();
+                        self.preview.skip(title, "In the recycle bin");
+                    }
+                },
+                "Group" => {
+                    let mut path = path.to_vec();
+                    path.push(child_text(node, "Name").trim().to_string());
+                    self.read_group(node, &path);
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn read_entry(&mut self, node: Node, path: &[String]) {
+        let strings = self.strings(node);
+        let string = |key: &str| strings.iter().find(|string| string.key == key).map(|string| string.value.clone()).unwrap_or_default();
+        let custom_data: HashMap<String, String> = child(node, "CustomData")
+            .into_iter()
+            .flat_map(|data| data.children().filter(|item| item.has_tag_name("Item")))
+            .map(|item| (child_text(item, "Key"), child_text(item, "Value")))
+            .collect();
+        // Entries exported by Keybolt say which type they are, any other entry is a login
+        let entry_type = custom_data
+            .get(TYPE_KEY)
+            .and_then(|name| EntryType::ALL.into_iter().find(|entry_type| entry_type.as_str() == name))
+            .unwrap_or(EntryType::Passwords);
+
+        let mut entry = new_entry(entry_type, self.folder);
+        let title = string("Title").trim().to_string();
+        entry["title"] = Value::from(title.as_str());
+        let mut used = vec!["Title"];
+        match entry_type {
+            EntryType::Passwords => {
+                for (key, name) in [("username", "UserName"), ("password", "Password"), ("url", "URL"), ("notes", "Notes")] {
+                    entry[key] = Value::from(string(name));
+                    used.push(name);
+                }
+                if let Some(otp) = OTP_KEYS.iter().map(|key| string(key)).find(|otp| !otp.trim().is_empty()) {
+                    entry["otpauth"] = Value::from(totp_uri(&otp));
+                }
+                used.extend(OTP_KEYS);
+            },
+            EntryType::Notes => {
+                entry["body"] = Value::from(string("Notes"));
+                used.push("Notes");
+            },
+            // Other types are stored as strings named after their fields
+            _ => {
+                for spec in schema::fields(entry_type).iter().filter(|spec| spec.key != "title") {
+                    if let Some(string) = strings.iter().find(|string| string.key == spec.label) {
+                        entry[spec.key] = match spec.kind {
+                            FieldKind::Flag => Value::Bool(string.value == "true"),
+                            _ => Value::from(string.value.as_str()),
+                        };
+                        used.push(spec.label);
+                    }
+                }
+            },
+        }
+
+        let mut custom: Vec<CustomField> = strings
+            .iter()
+            .filter(|string| !used.contains(&string.key.as_str()) && !string.value.is_empty())
+            .map(|string| CustomField {
+                label: string.key.clone(),
+                field_type: if string.protected { CustomFieldType::Hidden } else { CustomFieldType::Text },
+                value: string.value.clone(),
+            })
+            .collect();
+        if entry_type == EntryType::Passwords {
+            custom.extend(self.previous_passwords(node, &string("Password")));
+        }
+        if !custom.is_empty() {
+            set_custom_fields(&mut entry, &custom);
+        }
+
+        let tags: Vec<String> = child_text(node, "Tags").split([';', ',']).map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect();
+        entry["tags"] = Value::from(tags);
+        entry["favorite"] = Value::Bool(custom_data.get(FAVORITE_KEY).is_some_and(|favorite| favorite == "true"));
+        if let Some(times) = child(node, "Times") {
+            for (key, name) in [("created", "CreationTime"), ("modified", "LastModificationTime"), ("last_used", "LastAccessTime")] {
+                if let Some(time) = parse_time(&child_text(times, name)) {
+                    entry[key] = Value::from(time);
+                }
+            }
+        }
+        match entry_type {
+            EntryType::Cards => normalize_card(&mut entry),
+            EntryType::Identities => {
+                if let Some(documents) = custom_data.get(DOCUMENTS_KEY).and_then(|json| serde_json::from_str::<Vec<IdentityDocument>>(json).ok()) {
+                    set_documents(&mut entry, &documents);
+                }
+                normalize_identity(&mut entry);
+            },
+            _ => {},
+        }
+
+        let location = match path.is_empty() {
+            true => title,
+            false => format!("{} / {}", path.join(" / "), title),
+        };
+        let attachments = self.attachments(node, &location);
+        let imported = self.preview.add(entry_type, entry);
+        imported.folder_path = path.to_vec();
+        imported.attachments = attachments;
+    }
+
+    // Passwords the entry had before, oldest first, as hidden custom fields
+    fn previous_passwords(&self, node: Node, current: &str) -> Vec<CustomField> {
+        let mut seen = vec![current.to_string()];
+        let mut previous = Vec::new();
+        for old in child(node, "History").into_iter().flat_map(|history| history.children().filter(|entry| entry.has_tag_name("Entry"))) {
+            let Some(password) = self.strings(old).into_iter().find(|string| string.key == "Password").map(|string| string.value) else {
+                continue;
+            };
+            if password.is_empty() || seen.contains(&password) {
+                continue;
+            }
+            let changed = child(old, "Times").and_then(|times| parse_time(&child_text(times, "LastModificationTime"))).unwrap_or(0);
+            previous.push(previous_password(&password, changed));
+            seen.push(password);
+        }
+        previous
+    }
+
+    // Files attached to the entry, those over the attachment limits are left out and reported
+    fn attachments(&mut self, node: Node, location: &str) -> Vec<(String, Vec<u8>)> {
+        let mut attached = Vec::new();
+        let mut total = 0;
+        for binary in node.children().filter(|binary| binary.has_tag_name("Binary")) {
+            let name = child_text(binary, "Key");
+            let content = child(binary, "Value")
+                .and_then(|value| value.attribute("Ref"))
+                .and_then(|index| index.parse::<usize>().ok())
+                .and_then(|index| self.binaries.get(index));
+            let Some(content) = content else {
+                self.preview.skip(location, format!("Attachment {} is missing from the database", name));
+                continue;
+            };
+            let size = content.len() as u64;
+            let error = match size {
+                size if size > MAX_ATTACHMENT_SIZE => Some(AttachmentError::TooLarge(size)),
+                size if total + size > MAX_ENTRY_ATTACHMENTS_SIZE => Some(AttachmentError::EntryFull),
+                _ => None,
+            };
+            match error {
+                Some(e) => self.preview.skip(location, format!("Left out attachment {}: {}", name, e)),
+                None => {
+                    total += size;
+                    attached.push((name, content.clone()));
+                },
+            }
+        }
+        attached
+    }
+}
+
+// Entries of a KeePass KDBX 4 database, groups become folders and entries logins
+// unless they were exported from Keybolt as another type
+pub fn parse_kdbx(data: &[u8], password: &str, folder: Option<u64>) -> Result<ImportPreview, ImportError> {
+    let mut content = read_kdbx(data, password)?;
+    let document = Document::parse(&content.xml).map_err(|_| ImportError::UnrecognizedFormat("a KeePass database with a readable XML body"))?;
+    let protected = document
+        .descendants()
+        .filter(|node| node.has_tag_name("Value") && node.attribute("Protected").is_some_and(|protected| protected.eq_ignore_ascii_case("true")))
+        .map(|node| (node.id(), content.inner_stream.unprotect(node.text().unwrap_or(""))))
+        .collect();
+
+    let root = document.root_element();
+    let group = child(root, "Root")
+        .and_then(|root| child(root, "Group"))
+        .ok_or(ImportError::UnrecognizedFormat("a KeePass database with a root group"))?;
+    let recycle_bin = child(root, "Meta")
+        .filter(|meta| child_text(*meta, "RecycleBinEnabled") != "False")
+        .map(|meta| child_text(meta, "RecycleBinUUID"));
+    let mut reader = DatabaseReader {
+        protected,
+        binaries: &content.binaries,
+        recycle_bin,
+        folder,
+        preview: ImportPreview::new(ImportFormat::KeePassKdbx),
+    };
+    // The root group stands for the database itself, its name isn't a folder
+    reader.read_group(group, &[]);
+    Ok(reader.preview)
+}
+
+// Element text can't hold the control characters XML 1.0 forbids, they're dropped
+fn escape(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Builds the XML of an exported database, protecting secrets with the inner stream as they're written
+struct DatabaseWriter<'a> {
+    passphrase: &'a str,
+    xml: String,
+    depth: usize,
+    stream: InnerStream,
+    binaries: Vec<Vec<u8>>,
+    /// Index of each attachment blob already added, shared files are stored once
+    binary_index: HashMap<String, usize>,
+}
+
+impl DatabaseWriter<'_> {
+    fn open(&mut self, name: &str) {
+        self.xml.push_str(&format!("{}<{}>\n", "\t".repeat(self.depth), name));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.xml.push_str(&format!("{}</{}>\n", "\t".repeat(self.depth), name));
+    }
+
+    fn element(&mut self, name: &str, value: &str) {
+        let indent = "\t".repeat(self.depth);
+        match value.is_empty() {
+            true => self.xml.push_str(&format!("{}<{}/>\n", indent, name)),
+            false => self.xml.push_str(&format!("{}<{}>{}</{}>\n", indent, name, escape(value), name)),
+        }
+    }
+
+    fn string(&mut self, key: &str, value: &str, protected: bool) {
+        self.open("String");
+        self.element("Key", key);
+        match protected {
+            true => {
+                let value = self.stream.protect(value);
+                self.xml.push_str(&format!("{}<Value Protected=\"True\">{}</Value>\n", "\t".repeat(self.depth), value));
+            },
+            false => self.element("Value", value),
+        }
+        self.close("String");
+    }
+
+    fn times(&mut self, created: u64, modified: u64, used: u64) {
+        self.open("Times");
+        self.element("CreationTime", &format_time(created));
+        self.element("LastModificationTime", &format_time(modified));
+        self.element("LastAccessTime", &format_time(used));
+        self.element("ExpiryTime", &format_time(created));
+        self.element("Expires", "False");
+        self.element("UsageCount", "0");
+        self.element("LocationChanged", &format_time(modified));
+        self.close("Times");
+    }
+
+    // Entries of the folder, then its subfolders as groups
+    fn group_content(&mut self, vault: &Value, entries: &[(EntryType, Value)], folder: Option<u64>) -> Result<(), ImportError> {
+        for (entry_type, entry) in entries.iter().filter(|(_, entry)| entry_folder(entry) == folder) {
+            self.entry(*entry_type, entry)?;
+        }
+        let mut children: Vec<_> = folders(vault).into_iter().filter(|child| child.parent == folder).collect();
+        children.sort_by_key(|child| child.name.to_lowercase());
+        for child in children {
+            self.open("Group");
+            self.element("UUID", &new_uuid());
+            self.element("Name", &child.name);
+            self.element("IconID", FOLDER_ICON);
+            self.group_content(vault, entries, Some(child.id))?;
+            self.close("Group");
+        }
+        Ok(())
+    }
+
+    fn entry(&mut self, entry_type: EntryType, entry: &Value) -> Result<(), ImportError> {
+        let text = |key: &str| field_text(&entry[key]);
+        let uuid = new_uuid();
+        self.open("Entry");
+        self.element("UUID", &uuid);
+        self.element("IconID", icon(entry_type));
+        self.element("Tags", &entry_tags(entry).join(";"));
+        self.times(timestamp(entry, "created"), timestamp(entry, "modified"), timestamp(entry, "last_used"));
+
+        let mut custom = custom_fields(entry);
+        // Previous passwords go back into the entry's history
+        let mut history = Vec::new();
+        if entry_type == EntryType::Passwords {
+            custom.retain(|field| match previous_password_date(&field.label) {
+                Some(date) if field.field_type == CustomFieldType::Hidden => {
+                    history.push((date, field.value.clone()));
+                    false
+                },
+                _ => true,
+            });
+        }
+
+        let mut used: Vec<String> = ["Title", "UserName", "Password", "URL", "Notes"].map(String::from).to_vec();
+        self.string("Title", &text("title"), false);
+        match entry_type {
+            EntryType::Passwords => {
+                self.string("UserName", &text("username"), false);
+                self.string("Password", &text("password"), true);
+                self.string("URL", &text("url"), false);
+                self.string("Notes", &text("notes"), false);
+                if !text("otpauth").is_empty() {
+                    self.string(OTP_KEYS[0], &text("otpauth"), true);
+                    used.push(OTP_KEYS[0].to_string());
+                }
+            },
+            EntryType::Notes => self.string("Notes", &text("body"), false),
+            _ => {
+                // Notes of other types are a custom field, KeePass shows them in its notes
+                let notes = custom.iter().position(|field| field.label == "Notes").map(|i| custom.remove(i).value).unwrap_or_default();
+                self.string("Notes", &notes, false);
+                for spec in schema::fields(entry_type).iter().filter(|spec| spec.key != "title") {
+                    if !text(spec.key).is_empty() {
+                        self.string(spec.label, &text(spec.key), spec.kind.is_secret());
+                        used.push(spec.label.to_string());
+                    }
+                }
+            },
+        }
+        for field in custom {
+            // String keys are unique within an entry
+            let mut key = field.label.clone();
+            for n in 2.. {
+                if !used.contains(&key) {
+                    break;
+                }
+                key = format!("{} ({})", field.label, n);
+            }
+            self.string(&key, &field.value, field.field_type == CustomFieldType::Hidden);
+            used.push(key);
+        }
+
+        for attachment in attachments(entry) {
+            let index = match self.binary_index.get(&attachment.id) {
+                Some(index) => *index,
+                None => {
+                    self.binaries.push(read_attachment(self.passphrase, &attachment)?);
+                    self.binary_index.insert(attachment.id.clone(), self.binaries.len() - 1);
+                    self.binaries.len() - 1
+                },
+            };
+            self.open("Binary");
+            self.element("Key", &attachment.name);
+            self.xml.push_str(&format!("{}<Value Ref=\"{}\"/>\n", "\t".repeat(self.depth), index));
+            self.close("Binary");
+        }
+
+        let mut custom_data = vec![(TYPE_KEY, entry_type.as_str().to_string())];
+        if is_favorite(entry) {
+            custom_data.push((FAVORITE_KEY, String::from("true")));
+        }
+        if entry_type == EntryType::Identities && !documents(entry).is_empty() {
+            custom_data.push((DOCUMENTS_KEY, serde_json::to_string(&documents(entry))?));
+        }
+        self.open("CustomData");
+        for (key, value) in custom_data {
+            self.open("Item");
+            self.element("Key", key);
+            self.element("Value", &value);
+            self.close("Item");
+        }
+        self.close("CustomData");
+
+        if !history.is_empty() {
+            history.sort_by_key(|(date, _)| *date);
+            self.open("History");
+            for (date, password) in history {
+                self.open("Entry");
+                self.element("UUID", &uuid);
+                self.element("IconID", icon(entry_type));
+                self.times(timestamp(entry, "created"), date, date);
+                self.string("Title", &text("title"), false);
+                self.string("UserName", &text("username"), false);
+                self.string("Password", &password, true);
+                self.string("URL", &text("url"), false);
+                self.close("Entry");
+            }
+            self.close("History");
+        }
+        self.close("Entry");
+        Ok(())
+    }
+}
+
+// Standard KeePass icon closest to each entry type
+fn icon(entry_type: EntryType) -> &'static str {
+    match entry_type {
+        EntryType::Passwords => "0",
+        EntryType::Identities => "9",
+        EntryType::Cards => "66",
+        EntryType::Notes => "7",
+        EntryType::SshKeys => "29",
+        EntryType::ApiCredentials => "58",
+        EntryType::BankAccounts => "37",
+        EntryType::WifiNetworks => "12",
+    }
+}
+
+// Write every entry of the vault to a KDBX 4 database protected by its own password,
+// folders become groups. Returns how many entries were written.
+pub fn export_kdbx(passphrase: &str, vault: &Value, path: &str, password: &str, cipher: KdbxCipher) -> Result<usize, ImportError> {
+    if password.is_empty() {
+        return Err(ImportError::NoPassword);
+    }
+    let (data, count) = database(passphrase, vault, password, cipher, &Kdf::argon2id())?;
+    fs::write(path.trim(), data)?;
+    Ok(count)
+}
+
+// The database file for the vault's entries, with how many entries it holds
+fn database(passphrase: &str, vault: &Value, password: &str, cipher: KdbxCipher, kdf: &Kdf) -> Result<(Vec<u8>, usize), ImportError> {
+    let mut entries = Vec::new();
+    for entry_type in EntryType::ALL {
+        for entry in vault[entry_type.as_str()].as_array().into_iter().flatten() {
+            let mut entry = entry.clone();
+            decrypt_sensitive_fields(passphrase, &mut entry, entry_type)?;
+            entries.push((entry_type, entry));
+        }
+    }
+    // Entries in folders that no longer exist are written to the root group
+    let folder_ids: Vec<u64> = folders(vault).iter().map(|folder| folder.id).collect();
+    for (_, entry) in entries.iter_mut() {
+        if entry_folder(entry).is_some_and(|id| !folder_ids.contains(&id)) {
+            entry["folder"] = Value::Null;
+        }
+    }
+
+    let (stream, stream_key) = InnerStream::generate();
+    let mut writer = DatabaseWriter { passphrase, xml: String::new(), depth: 0, stream, binaries: Vec::new(), binary_index: HashMap::new() };
+    writer.xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"yes\"?>\n");
+    writer.open("KeePassFile");
+    writer.open("Meta");
+    writer.element("Generator", DATABASE_NAME);
+    writer.element("DatabaseName", DATABASE_NAME);
+    writer.element("RecycleBinEnabled", "False");
+    writer.close("Meta");
+    writer.open("Root");
+    writer.open("Group");
+    writer.element("UUID", &new_uuid());
+    writer.element("Name", DATABASE_NAME);
+    writer.element("IconID", FOLDER_ICON);
+    writer.element("IsExpanded", "True");
+    writer.group_content(vault, &entries, None)?;
+    writer.close("Group");
+    writer.close("Root");
+    writer.close("KeePassFile");
+
+    let data = write_kdbx(password, cipher, kdf, &stream_key, &writer.xml, &writer.binaries)?;
+    Ok((data, entries.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use argon2::Algorithm;
+    use serde_json::json;
+
+    use crate::secure::encrypt::encrypt_sensitive_fields;
+    use crate::vault::custom_field::set_custom_fields;
+    use crate::vault::folder::create_folder;
+
+    #[test]
+    fn export_and_import() {
+        let mut vault = json!({ "passwords": [], "identities": [], "cards": [], "notes": [], "ssh_keys": [], "api_credentials": [], "bank_accounts": [], "wifi_networks": [], "folders": [] });
+        let work = create_folder(&mut vault, "Work", None).unwrap();
+        let mail = create_folder(&mut vault, "Mail", Some(work)).unwrap();
+
+        let mut login = new_entry(EntryType::Passwords, Some(mail));
+        login["title"] = json!("Mail <&> \"x\"");
+        login["username"] = json!("bob");
+        login["password"] = json!("s3cret");
+        login["url"] = json!("https://mail.example.com");
+        login["tags"] = json!(["work", "mail"]);
+        login["favorite"] = json!(true);
+        set_custom_fields(&mut login, &[
+            CustomField { label: String::from("Account"), field_type: CustomFieldType::Text, value: String::from("12345") },
+            CustomField { label: String::from("PIN"), field_type: CustomFieldType::Hidden, value: String::from("1234") },
+            previous_password("old1", 1_680_307_200),
+        ]);
+        encrypt_sensitive_fields("passphrase", &mut login, EntryType::Passwords).unwrap();
+        vault["passwords"] = json!([login]);
+        let mut note = new_entry(EntryType::Notes, None);
+        note["title"] = json!("Note");
+        note["body"] = json!("first line\nsecond line");
+        encrypt_sensitive_fields("passphrase", &mut note, EntryType::Notes).unwrap();
+        vault["notes"] = json!([note]);
+
+        let kdf = Kdf::Argon2 { algorithm: Algorithm::Argon2id, salt: vec![1; 32], memory: 64 * 1024, iterations: 1, parallelism: 1, version: 0x13 };
+        let (data, count) = database("passphrase", &vault, "pw", KdbxCipher::ChaCha20, &kdf).unwrap();
+        assert_eq!(count, 2);
+
+        let preview = parse_kdbx(&data, "pw", None).unwrap();
+        assert!(preview.skipped.is_empty());
+        let imported = preview.entries.iter().find(|imported| imported.entry_type == EntryType::Passwords).unwrap();
+        assert_eq!(imported.folder_path, ["Work", "Mail"]);
+        let entry = &imported.entry;
+        assert_eq!(entry["title"], "Mail <&> \"x\"");
+        assert_eq!(entry["username"], "bob");
+        assert_eq!(entry["password"], "s3cret");
+        assert_eq!(entry["url"], "https://mail.example.com");
+        assert_eq!(entry["tags"], json!(["work", "mail"]));
+        assert_eq!(entry["favorite"], true);
+        assert_eq!(entry["custom_fields"], json!([
+            { "label": "Account", "type": "text", "value": "12345" },
+            { "label": "PIN", "type": "hidden", "value": "1234" },
+            { "label": "Previous Password (2023-04-01)", "type": "hidden", "value": "old1" },
+        ]));
+
+        let note = preview.entries.iter().find(|imported| imported.entry_type == EntryType::Notes).unwrap();
+        assert!(note.folder_path.is_empty());
+        assert_eq!(note.entry["body"], "first line\nsecond line");
+        assert!(matches!(parse_kdbx(&data, "wrong", None), Err(ImportError::Kdbx(_))));
+    }
+}
@@ -0,0 +1,195 @@
+use serde_json::Value;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::import::csv::{column_index, parse_csv};
+use crate::import::{url_host, ImportError, ImportFormat, ImportPreview};
+use crate::vault::card::normalize_card;
+use crate::vault::custom_field::{totp_uri, CustomField, CustomFieldType};
+use crate::vault::entry::new_entry;
+use crate::vault::identity::normalize_identity;
+
+// Secure notes are exported as rows with this URL
+const SECURE_NOTE_URL: &str = "http://sn";
+
+const MONTHS: [&str; 12] = ["january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november", "december"];
+
+/// A typed secure note: its "NoteType:" line, the "Key:Value" lines and the free text after "Notes:"
+struct TypedNote {
+    note_type: String,
+    fields: Vec<(String, String)>,
+    notes: String,
+}
+
+impl TypedNote {
+    fn parse(extra: &str) -> Option<TypedNote> {
+        let mut lines = extra.lines();
+        let note_type = lines.next()?.strip_prefix("NoteType:")?.trim().to_string();
+        let mut fields: Vec<(String, String)> = Vec::new();
+        let mut notes = Vec::new();
+        let mut in_notes = false;
+        for line in lines {
+            if in_notes {
+                notes.push(line);
+            } else if let Some(rest) = line.strip_prefix("Notes:") {
+                in_notes = true;
+                notes.push(rest);
+            } else if let Some((key, value)) = line.split_once(':') {
+                fields.push((key.trim().to_string(), value.trim().to_string()));
+            } else if let Some((_, value)) = fields.last_mut() {
+                // Values with line breaks continue on the following lines
+                value.push('\n');
+                value.push_str(line);
+            }
+        }
+        Some(TypedNote { note_type, fields, notes: notes.join("\n").trim().to_string() })
+    }
+
+    // Value of a field, each field is only used once
+    fn take(&mut self, key: &str) -> String {
+        match self.fields.iter().position(|(name, _)| name == key) {
+            Some(i) => phone_number(&self.fields.remove(i).1),
+            None => String::new(),
+        }
+    }
+
+    // Fields not taken, as custom fields. Nothing says which are sensitive, so passwords and PINs are guessed by name.
+    // Language is the note's form language and empty month/year fields are a lone ",".
+    fn remaining(self) -> Vec<CustomField> {
+        let mut custom: Vec<CustomField> = self
+            .fields
+            .into_iter()
+            .filter(|(label, _)| label != "Language")
+            .map(|(label, value)| (label, phone_number(&value)))
+            .filter(|(_, value)| !value.trim_matches(',').trim().is_empty())
+            .map(|(label, value)| {
+                let lower = label.to_lowercase();
+                let field_type = if lower.contains("password") || lower == "pin" { CustomFieldType::Hidden } else { CustomFieldType::Text };
+                CustomField { label, field_type, value }
+            })
+            .collect();
+        if !self.notes.is_empty() {
+            custom.push(CustomField { label: String::from("Notes"), field_type: CustomFieldType::Text, value: self.notes });
+        }
+        custom
+    }
+}
+
+// Phone numbers are stored as JSON like {"num":"5551234","ext":"","cc3l":"USA"}
+fn phone_number(value: &str) -> String {
+    match serde_json::from_str::<Value>(value) {
+        Ok(phone) if phone.is_object() => phone["num"].as_str().unwrap_or("").trim().to_string(),
+        _ => value.to_string(),
+    }
+}
+
+// "June,2025" expiry dates of payment cards as "06/2025"
+fn card_expiry(value: &str) -> String {
+    let Some((month, year)) = value.split_once(',') else {
+        return value.to_string();
+    };
+    match MONTHS.iter().position(|name| name.eq_ignore_ascii_case(month.trim())) {
+        Some(i) => format!("{:02}/{}", i + 1, year.trim()),
+        None => value.to_string(),
+    }
+}
+
+fn card(mut note: TypedNote, folder: Option<u64>) -> (EntryType, Value, Vec<CustomField>) {
+    let mut entry = new_entry(EntryType::Cards, folder);
+    entry["name"] = Value::from(note.take("Name on Card"));
+    entry["card_number"] = Value::from(note.take("Number"));
+    entry["security_code"] = Value::from(note.take("Security Code"));
+    entry["expiration_date"] = Value::from(card_expiry(&note.take("Expiration Date")));
+    // The brand follows from the card number
+    note.take("Type");
+    normalize_card(&mut entry);
+    (EntryType::Cards, entry, note.remaining())
+}
+
+fn address(mut note: TypedNote, folder: Option<u64>) -> (EntryType, Value, Vec<CustomField>) {
+    let mut entry = new_entry(EntryType::Identities, folder);
+    entry["first_name"] = Value::from(note.take("First Name"));
+    entry["middle_initial"] = Value::from(note.take("Middle Name").chars().next().map(String::from).unwrap_or_default());
+    entry["last_name"] = Value::from(note.take("Last Name"));
+    let street: Vec<String> = ["Address 1", "Address 2", "Address 3"].iter().map(|key| note.take(key)).filter(|line| !line.is_empty()).collect();
+    entry["address"] = Value::from(street.join(", "));
+    entry["city"] = Value::from(note.take("City / Town"));
+    entry["state"] = Value::from(note.take("State"));
+    entry["zipcode"] = Value::from(note.take("Zip / Postal Code"));
+    entry["country"] = Value::from(note.take("Country"));
+    entry["phone"] = Value::from(note.take("Phone"));
+    entry["email"] = Value::from(note.take("Email Address"));
+    normalize_identity(&mut entry);
+    (EntryType::Identities, entry, note.remaining())
+}
+
+// Secure notes: payment cards and addresses get their own entry types, other typed notes
+// keep their fields as custom fields
+fn secure_note(extra: &str, folder: Option<u64>) -> (EntryType, Value, Vec<CustomField>) {
+    match TypedNote::parse(extra) {
+        Some(note) if note.note_type == "Credit Card" => card(note, folder),
+        Some(note) if note.note_type == "Address" => address(note, folder),
+        Some(mut note) => {
+            let mut entry = new_entry(EntryType::Notes, folder);
+            entry["body"] = Value::from(std::mem::take(&mut note.notes));
+            (EntryType::Notes, entry, note.remaining())
+        },
+        None => {
+            let mut entry = new_entry(EntryType::Notes, folder);
+            entry["body"] = Value::from(extra);
+            (EntryType::Notes, entry, Vec::new())
+        },
+    }
+}
+
+// LastPass exports url,username,password,totp,extra,name,grouping,fav. Nested folders
+// are separated by backslashes in the grouping column.
+pub fn parse_lastpass_csv(text: &str, folder: Option<u64>) -> Result<ImportPreview, ImportError> {
+    let records = parse_csv(text).map_err(ImportError::UnterminatedQuote)?;
+    let mut records = records.into_iter();
+    let header = records.next().ok_or(ImportError::UnrecognizedFormat("a LastPass export with url, username, password, extra and grouping columns"))?;
+    let column = |name: &str| column_index(&header.fields, &[name]);
+    let (Some(url), Some(username), Some(password), Some(extra), Some(name), Some(grouping)) =
+        (column("url"), column("username"), column("password"), column("extra"), column("name"), column("grouping"))
+    else {
+        return Err(ImportError::UnrecognizedFormat("a LastPass export with url, username, password, extra and grouping columns"));
+    };
+    let totp = column("totp");
+    let favorite = column("fav");
+
+    let mut preview = ImportPreview::new(ImportFormat::LastPassCsv);
+    for record in records {
+        if record.fields.len() != header.fields.len() {
+            preview.skip(format!("Line {}", record.line), format!("Expected {} columns, found {}", header.fields.len(), record.fields.len()));
+            continue;
+        }
+        let url = record.field(Some(url));
+
+        let (entry_type, mut entry, custom) = if url == SECURE_NOTE_URL {
+            secure_note(&record.raw(Some(extra)), folder)
+        } else {
+            let mut entry = new_entry(EntryType::Passwords, folder);
+            entry["url"] = Value::from(url.as_str());
+            entry["username"] = Value::from(record.field(Some(username)));
+            entry["password"] = Value::from(record.raw(Some(password)));
+            entry["notes"] = Value::from(record.raw(Some(extra)));
+            if !record.field(totp).is_empty() {
+                entry["otpauth"] = Value::from(totp_uri(&record.field(totp)));
+            }
+            (EntryType::Passwords, entry, Vec::new())
+        };
+        let title = match record.field(Some(name)) {
+            name if !name.is_empty() => name,
+            _ if entry_type == EntryType::Passwords && !url.is_empty() => url_host(&url),
+            _ => record.field(Some(username)),
+        };
+        entry["title"] = Value::from(title);
+        entry["favorite"] = Value::Bool(record.field(favorite) == "1");
+        if !custom.is_empty() {
+            entry["custom_fields"] = serde_json::to_value(custom)?;
+        }
+
+        let imported = preview.add(entry_type, entry);
+        imported.folder_path = record.field(Some(grouping)).split('\\').map(|name| name.trim().to_string()).filter(|name| !name.is_empty()).collect();
+    }
+    Ok(preview)
+}
@@ -0,0 +1,306 @@
+pub mod bitwarden;
+pub mod browser;
+pub mod csv;
+pub mod kdbx;
+pub mod keepass;
+pub mod lastpass;
+pub mod onepassword;
+pub mod pass;
+
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::import::kdbx::KdbxError;
+use crate::secure::attachment::{store_attachment, AttachmentError};
+use crate::secure::encrypt::{encrypt_sensitive_fields, EncryptError};
+use crate::vault::custom_field::{CustomField, CustomFieldType};
+use crate::vault::date::{days_from_civil, format_date, parse_date};
+use crate::vault::entry::now;
+use crate::vault::folder::{entry_folder, folder_at_path, set_entry_folder};
+use crate::vault::search::field_text;
+
+/// Formats entries can be imported from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Saved passwords exported by Chrome, Firefox and other browsers
+    BrowserCsv,
+    /// Bitwarden's unencrypted JSON export
+    BitwardenJson,
+    /// KeePass and KeePassXC databases, opened with their password
+    KeePassKdbx,
+    /// A pass password-store directory, decrypted file by file with gpg
+    PassStore,
+    /// 1Password's .1pux export, a zip archive with the items in export.data
+    OnePassword1pux,
+    /// LastPass' CSV export, with secure notes in the extra column
+    LastPassCsv,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 6] = [
+        ImportFormat::BrowserCsv,
+        ImportFormat::BitwardenJson,
+        ImportFormat::KeePassKdbx,
+        ImportFormat::PassStore,
+        ImportFormat::OnePassword1pux,
+        ImportFormat::LastPassCsv,
+    ];
+
+    // Entries found at the path, nothing is added to the vault yet
+    fn parse(&self, path: &str, options: &ImportOptions, folder: Option<u64>) -> Result<ImportPreview, ImportError> {
+        let path = path.trim();
+        match self {
+            ImportFormat::BrowserCsv => browser::parse_browser_csv(&fs::read_to_string(path)?, folder),
+            ImportFormat::BitwardenJson => bitwarden::parse_bitwarden_json(&fs::read_to_string(path)?, folder),
+            ImportFormat::KeePassKdbx => keepass::parse_kdbx(&fs::read(path)?, options.password, folder),
+            ImportFormat::PassStore => pass::parse_password_store(path, options.gpg_command, folder),
+            ImportFormat::OnePassword1pux => onepassword::parse_1pux(&fs::read(path)?, folder),
+            ImportFormat::LastPassCsv => lastpass::parse_lastpass_csv(&fs::read_to_string(path)?, folder),
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            ImportFormat::BrowserCsv => "Chrome/Firefox CSV",
+            ImportFormat::BitwardenJson => "Bitwarden JSON",
+            ImportFormat::KeePassKdbx => "KeePass KDBX 4",
+            ImportFormat::PassStore => "pass (password-store)",
+            ImportFormat::OnePassword1pux => "1Password 1PUX",
+            ImportFormat::LastPassCsv => "LastPass CSV",
+        })
+    }
+}
+
+/// What some formats need besides the file to read it
+pub struct ImportOptions<'a> {
+    /// Password of an encrypted export, e.g. a KeePass database
+    pub password: &'a str,
+    /// Command pass files are decrypted with, e.g. "gpg" or "gpg2 --pinentry-mode loopback"
+    pub gpg_command: &'a str,
+}
+
+/// An entry read from an import file, its secrets are still in plain text
+#[derive(Clone, Debug)]
+pub struct ImportedEntry {
+    pub entry_type: EntryType,
+    pub entry: Value,
+    /// Matches an entry already in the vault or earlier in the file
+    pub duplicate: bool,
+    /// Folder names from the import file, created below the folder being browsed
+    pub folder_path: Vec<String>,
+    /// Files to attach once the entry is added, by name
+    pub attachments: Vec<(String, Vec<u8>)>,
+}
+
+/// A record of the import file that couldn't be turned into an entry
+#[derive(Clone, Debug)]
+pub struct SkippedRow {
+    /// Where the record is in the file, e.g. "Line 12" or "Item 3"
+    pub location: String,
+    pub reason: String,
+}
+
+/// What an import would add, shown for review before anything is saved
+#[derive(Clone, Debug)]
+pub struct ImportPreview {
+    pub format: ImportFormat,
+    pub entries: Vec<ImportedEntry>,
+    pub skipped: Vec<SkippedRow>,
+}
+
+impl ImportPreview {
+    pub fn new(format: ImportFormat) -> ImportPreview {
+        ImportPreview { format, entries: Vec::new(), skipped: Vec::new() }
+    }
+
+    // Returns the added entry so formats with folders can set its path
+    pub fn add(&mut self, entry_type: EntryType, entry: Value) -> &mut ImportedEntry {
+        self.entries.push(ImportedEntry { entry_type, entry, duplicate: false, folder_path: Vec::new(), attachments: Vec::new() });
+        self.entries.last_mut().expect("an entry was just added")
+    }
+
+    pub fn skip(&mut self, location: impl Into<String>, reason: impl Into<String>) {
+        self.skipped.push(SkippedRow { location: location.into(), reason: reason.into() });
+    }
+
+    pub fn duplicates(&self) -> usize {
+        self.entries.iter().filter(|imported| imported.duplicate).count()
+    }
+
+    // Entries that would be added for each type, e.g. "Password: 12, Card: 3"
+    pub fn summary(&self, skip_duplicates: bool) -> String {
+        let counts: Vec<String> = EntryType::ALL
+            .into_iter()
+            .filter_map(|entry_type| {
+                let count = self
+                    .entries
+                    .iter()
+                    .filter(|imported| imported.entry_type == entry_type && !(skip_duplicates && imported.duplicate))
+                    .count();
+                (count > 0).then(|| format!("{}: {}", entry_type.label(), count))
+            })
+            .collect();
+        if counts.is_empty() {
+            String::from("Nothing to import")
+        } else {
+            counts.join(", ")
+        }
+    }
+
+    // Flag entries that are already in the vault, or that repeat an earlier entry of the file
+    pub fn mark_duplicates(&mut self, vault: &Value) {
+        let mut seen: HashSet<String> = EntryType::ALL
+            .into_iter()
+            .flat_map(|entry_type| {
+                vault[entry_type.as_str()]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(move |entry| duplicate_key(entry_type, entry))
+            })
+            .collect();
+        for imported in self.entries.iter_mut() {
+            imported.duplicate = !seen.insert(duplicate_key(imported.entry_type, &imported.entry));
+        }
+    }
+}
+
+// Passwords an entry had before are kept as hidden custom fields labelled with the date
+// they were replaced on, e.g. "Previous Password (2023-04-01)"
+const PREVIOUS_PASSWORD: &str = "Previous Password";
+
+pub fn previous_password(password: &str, replaced: u64) -> CustomField {
+    CustomField {
+        label: format!("{} ({})", PREVIOUS_PASSWORD, format_date(replaced)),
+        field_type: CustomFieldType::Hidden,
+        value: password.to_string(),
+    }
+}
+
+// Unix time of the day in a previous password's label
+pub fn previous_password_date(label: &str) -> Option<u64> {
+    let date = label.strip_prefix(PREVIOUS_PASSWORD)?.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let (year, month, day) = parse_date(date)?;
+    u64::try_from(days_from_civil(year, month, day) * 86_400).ok()
+}
+
+// Host of a URL without scheme, "www.", credentials or path, e.g. "accounts.example.com"
+pub fn url_host(url: &str) -> String {
+    let url = url.trim().to_ascii_lowercase();
+    let rest = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    host.strip_prefix("www.").unwrap_or(host).to_string()
+}
+
+// What makes two entries the same: the site and username of logins, the title and
+// most identifying field otherwise. Secrets are never compared, stored ones are encrypted.
+fn duplicate_key(entry_type: EntryType, entry: &Value) -> String {
+    let text = |key: &str| field_text(&entry[key]).trim().to_lowercase();
+    match entry_type {
+        EntryType::Passwords if !(text("url").is_empty() && text("username").is_empty()) => {
+            format!("{}\n{}\n{}", entry_type.as_str(), url_host(&text("url")), text("username"))
+        },
+        _ => format!("{}\n{}\n{}", entry_type.as_str(), text("title"), text(entry_type.subtitle_field())),
+    }
+}
+
+// Read an import file into a preview. Duplicates are flagged with mark_duplicates
+// against the vault as it is when the preview is shown.
+pub fn preview_import(format: ImportFormat, path: &str, options: &ImportOptions, folder: Option<u64>) -> Result<ImportPreview, ImportError> {
+    format.parse(path, options, folder)
+}
+
+// Encrypt the previewed entries and add them to the vault, returns how many were added
+pub fn apply_import(passphrase: &str, vault: &mut Value, preview: ImportPreview, skip_duplicates: bool) -> Result<usize, ImportError> {
+    let mut added = 0;
+    for mut imported in preview.entries.into_iter().filter(|imported| !(skip_duplicates && imported.duplicate)) {
+        if !imported.folder_path.is_empty() {
+            let folder = folder_at_path(vault, entry_folder(&imported.entry), &imported.folder_path);
+            set_entry_folder(&mut imported.entry, folder);
+        }
+        for (name, content) in &imported.attachments {
+            store_attachment(passphrase, vault, &mut imported.entry, name, content, now())?;
+        }
+        encrypt_sensitive_fields(passphrase, &mut imported.entry, imported.entry_type)?;
+        if let Some(entries) = vault[imported.entry_type.as_str()].as_array_mut() {
+            entries.push(imported.entry);
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+// Errors raised when reading an import file
+#[derive(Debug)]
+pub enum ImportError {
+    Io(io::Error),
+    Encrypt(EncryptError),
+    Json(serde_json::Error),
+    Kdbx(KdbxError),
+    Attachment(AttachmentError),
+    /// The export is protected with a password, only plain exports can be read
+    PasswordProtected,
+    /// The file isn't in the chosen format, with what was expected
+    UnrecognizedFormat(&'static str),
+    /// A quoted value starting on this line is never closed
+    UnterminatedQuote(usize),
+    /// Exported databases need a password of their own
+    NoPassword,
+    /// The gpg command couldn't be run at all
+    Gpg(String, io::Error),
+}
+
+impl From<io::Error> for ImportError {
+    fn from(err: io::Error) -> ImportError {
+        ImportError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ImportError {
+    fn from(err: serde_json::Error) -> ImportError {
+        ImportError::Json(err)
+    }
+}
+
+impl From<KdbxError> for ImportError {
+    fn from(err: KdbxError) -> ImportError {
+        ImportError::Kdbx(err)
+    }
+}
+
+impl From<AttachmentError> for ImportError {
+    fn from(err: AttachmentError) -> ImportError {
+        ImportError::Attachment(err)
+    }
+}
+
+impl From<EncryptError> for ImportError {
+    fn from(err: EncryptError) -> ImportError {
+        ImportError::Encrypt(err)
+    }
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(e) => write!(f, "IO error: {}", e),
+            ImportError::Encrypt(e) => write!(f, "{}", e),
+            ImportError::Json(e) => write!(f, "Invalid JSON: {}", e),
+            ImportError::Kdbx(e) => write!(f, "{}", e),
+            ImportError::Attachment(e) => write!(f, "{}", e),
+            ImportError::PasswordProtected => write!(f, "The export is password protected, export it again without a password"),
+            ImportError::UnrecognizedFormat(expected) => write!(f, "Not a recognized file, expected {}", expected),
+            ImportError::UnterminatedQuote(line) => write!(f, "The quoted value starting on line {} is never closed", line),
+            ImportError::NoPassword => write!(f, "Enter a password for the KeePass database"),
+            ImportError::Gpg(command, e) => write!(f, "Couldn't run {}: {}", command, e),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use serde_json::Value;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::import::{previous_password, ImportError, ImportFormat, ImportPreview};
+use crate::secure::attachment::{AttachmentError, MAX_ATTACHMENT_SIZE};
+use crate::vault::card::normalize_card;
+use crate::vault::custom_field::{totp_uri, CustomField, CustomFieldType};
+use crate::vault::date::format_date;
+use crate::vault::entry::new_entry;
+use crate::vault::identity::normalize_identity;
+use crate::vault::search::field_text;
+
+// Item categories of a 1Password export. Secure notes ("003") and anything else are imported as notes.
+const LOGIN: &str = "001";
+const CREDIT_CARD: &str = "002";
+const IDENTITY: &str = "004";
+const PASSWORD: &str = "005";
+const DOCUMENT: &str = "006";
+
+fn text(value: &Value) -> String {
+    field_text(value).trim().to_string()
+}
+
+/// A field from one of an item's sections. Its value is an object keyed by the
+/// kind of value, e.g. {"concealed": "..."} or {"monthYear": 202512}.
+struct SectionField<'a> {
+    id: String,
+    label: String,
+    value: &'a Value,
+}
+
+impl SectionField<'_> {
+    fn kind(&self) -> &str {
+        self.value.as_object().and_then(|value| value.keys().next()).map_or("", |kind| kind.as_str())
+    }
+
+    fn inner(&self) -> &Value {
+        self.value.as_object().and_then(|value| value.values().next()).unwrap_or(&Value::Null)
+    }
+
+    // Value in the form Keybolt stores it, with the custom field type that fits it best
+    fn to_text(&self) -> (String, CustomFieldType) {
+        let inner = self.inner();
+        match self.kind() {
+            "concealed" => (field_text(inner), CustomFieldType::Hidden),
+            "totp" => (totp_uri(&text(inner)), CustomFieldType::Totp),
+            "url" => (text(inner), CustomFieldType::Url),
+            "email" => (inner.get("email_address").map(text).unwrap_or_else(|| text(inner)), CustomFieldType::Email),
+            "date" => (inner.as_u64().map(format_date).unwrap_or_default(), CustomFieldType::Date),
+            // Stored as YYYYMM
+            "monthYear" => (inner.as_u64().map(|date| format!("{:02}/{}", date % 100, date / 100)).unwrap_or_default(), CustomFieldType::Text),
+            "address" => {
+                let parts: Vec<String> = ["street", "city", "state", "zip", "country"].iter().map(|key| text(&inner[*key])).filter(|part| !part.is_empty()).collect();
+                (parts.join(", "), CustomFieldType::Text)
+            },
+            "sshKey" => (field_text(&inner["privateKey"]), CustomFieldType::Hidden),
+            _ => (text(inner), CustomFieldType::Text),
+        }
+    }
+
+    fn to_custom_field(&self) -> Option<CustomField> {
+        let (value, field_type) = self.to_text();
+        (!value.trim().is_empty()).then(|| CustomField { label: self.label.clone(), field_type, value })
+    }
+}
+
+fn section_fields(item: &Value) -> Vec<SectionField<'_>> {
+    item["details"]["sections"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|section| section["fields"].as_array().into_iter().flatten())
+        .map(|field| {
+            let id = text(&field["id"]);
+            let label = match text(&field["title"]) {
+                title if title.is_empty() => id.clone(),
+                title => title,
+            };
+            SectionField { id, label, value: &field["value"] }
+        })
+        .collect()
+}
+
+// Logins, credit cards, identities and secure notes of a 1Password .1pux export.
+// Each vault becomes a folder, other categories are kept as secure notes with their fields.
+pub fn parse_1pux(data: &[u8], folder: Option<u64>) -> Result<ImportPreview, ImportError> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|_| ImportError::UnrecognizedFormat("a 1Password .1pux export"))?;
+    let mut json = String::new();
+    archive
+        .by_name("export.data")
+        .map_err(|_| ImportError::UnrecognizedFormat("a 1Password .1pux export with an export.data file"))?
+        .read_to_string(&mut json)?;
+    let export: Value = serde_json::from_str(&json)?;
+
+    let mut preview = ImportPreview::new(ImportFormat::OnePassword1pux);
+    let vaults = export["accounts"].as_array().into_iter().flatten().flat_map(|account| account["vaults"].as_array().into_iter().flatten());
+    for vault in vaults {
+        let vault_name = text(&vault["attrs"]["name"]);
+        for (i, item) in vault["items"].as_array().into_iter().flatten().enumerate() {
+            let title = text(&item["overview"]["title"]);
+            let location = match title.is_empty() {
+                true => format!("{} / Item {}", vault_name, i + 1),
+                false => format!("{} / {}", vault_name, title),
+            };
+            if item["state"] == "archived" {
+                preview.skip(location, "Archived");
+                continue;
+            }
+
+            let fields = section_fields(item);
+            let (entry_type, mut entry, mut custom) = match item["categoryUuid"].as_str().unwrap_or("") {
+                LOGIN | PASSWORD => login(item, &fields, folder),
+                CREDIT_CARD => card(&fields, folder),
+                IDENTITY => identity(&fields, folder),
+                _ => {
+                    let mut note = new_entry(EntryType::Notes, folder);
+                    note["body"] = Value::from(field_text(&item["details"]["notesPlain"]));
+                    (EntryType::Notes, note, fields.iter().filter_map(SectionField::to_custom_field).collect())
+                },
+            };
+            entry["title"] = Value::from(title);
+            // Only logins and notes have a notes field of their own
+            let notes = field_text(&item["details"]["notesPlain"]);
+            match entry_type {
+                EntryType::Passwords => entry["notes"] = Value::from(notes),
+                EntryType::Notes => {},
+                _ if !notes.trim().is_empty() => custom.push(CustomField { label: String::from("Notes"), field_type: CustomFieldType::Text, value: notes }),
+                _ => {},
+            }
+            if !custom.is_empty() {
+                entry["custom_fields"] = serde_json::to_value(custom)?;
+            }
+            let tags: Vec<String> = item["overview"]["tags"].as_array().into_iter().flatten().map(text).filter(|tag| !tag.is_empty()).collect();
+            entry["tags"] = Value::from(tags);
+            entry["favorite"] = Value::Bool(item["favIndex"].as_u64().unwrap_or(0) > 0);
+            for (key, time) in [("created", "createdAt"), ("modified", "updatedAt")] {
+                if let Some(time) = item[time].as_u64() {
+                    entry[key] = Value::from(time);
+                }
+            }
+
+            let attachment = match item["categoryUuid"].as_str() {
+                Some(DOCUMENT) => document_file(&mut archive, item, &location, &mut preview),
+                _ => None,
+            };
+            let imported = preview.add(entry_type, entry);
+            if !vault_name.is_empty() {
+                imported.folder_path = vec![vault_name.clone()];
+            }
+            imported.attachments.extend(attachment);
+        }
+    }
+    Ok(preview)
+}
+
+fn login(item: &Value, fields: &[SectionField], folder: Option<u64>) -> (EntryType, Value, Vec<CustomField>) {
+    let mut entry = new_entry(EntryType::Passwords, folder);
+    let overview = &item["overview"];
+    let url = match text(&overview["url"]) {
+        url if url.is_empty() => text(&overview["urls"][0]["url"]),
+        url => url,
+    };
+    entry["url"] = Value::from(url.as_str());
+    // Login fields without a designation are other inputs of the sign-in form
+    for field in item["details"]["loginFields"].as_array().into_iter().flatten() {
+        match field["designation"].as_str() {
+            Some("username") if entry["username"] == "" => entry["username"] = Value::from(text(&field["value"])),
+            Some("password") if entry["password"] == "" => entry["password"] = Value::from(field_text(&field["value"])),
+            _ => {},
+        }
+    }
+    // Standalone password items have no login fields, their secret is kept in details.password
+    if entry["password"] == "" {
+        entry["password"] = Value::from(field_text(&item["details"]["password"]));
+    }
+
+    let mut custom = Vec::new();
+    for field in fields {
+        match field.kind() {
+            "totp" if entry["otpauth"] == "" => entry["otpauth"] = Value::from(field.to_text().0),
+            _ => custom.extend(field.to_custom_field()),
+        }
+    }
+    // A login has one URL field, the other websites are kept as custom fields
+    let other_urls = overview["urls"].as_array().into_iter().flatten().map(|other| text(&other["url"])).filter(|other| !other.is_empty() && *other != url);
+    custom.extend(other_urls.enumerate().map(|(i, other)| CustomField { label: format!("URL {}", i + 2), field_type: CustomFieldType::Url, value: other }));
+
+    let mut seen = vec![field_text(&entry["password"])];
+    for old in item["details"]["passwordHistory"].as_array().into_iter().flatten() {
+        let password = field_text(&old["value"]);
+        if !password.is_empty() && !seen.contains(&password) {
+            custom.push(previous_password(&password, old["time"].as_u64().unwrap_or(0)));
+            seen.push(password);
+        }
+    }
+    (EntryType::Passwords, entry, custom)
+}
+
+fn card(fields: &[SectionField], folder: Option<u64>) -> (EntryType, Value, Vec<CustomField>) {
+    let mut entry = new_entry(EntryType::Cards, folder);
+    let mut custom = Vec::new();
+    for field in fields {
+        let key = match field.id.as_str() {
+            "cardholder" => "name",
+            "ccnum" => "card_number",
+            "cvv" => "security_code",
+            "expiry" => "expiration_date",
+            // The brand follows from the card number
+            "type" => continue,
+            _ => {
+                custom.extend(field.to_custom_field());
+                continue;
+            },
+        };
+        entry[key] = Value::from(field.to_text().0);
+    }
+    normalize_card(&mut entry);
+    (EntryType::Cards, entry, custom)
+}
+
+fn identity(fields: &[SectionField], folder: Option<u64>) -> (EntryType, Value, Vec<CustomField>) {
+    let mut entry = new_entry(EntryType::Identities, folder);
+    let mut custom = Vec::new();
+    for field in fields {
+        let (value, _) = field.to_text();
+        match field.id.as_str() {
+            "firstname" => entry["first_name"] = Value::from(value),
+            "initial" => entry["middle_initial"] = Value::from(value.chars().next().map(String::from).unwrap_or_default()),
+            "lastname" => entry["last_name"] = Value::from(value),
+            "defphone" => entry["phone"] = Value::from(value),
+            "email" if entry["email"] == "" => entry["email"] = Value::from(value),
+            "address" if field.kind() == "address" => {
+                let address = field.inner();
+                for (key, part) in [("address", "street"), ("city", "city"), ("state", "state"), ("zipcode", "zip"), ("country", "country")] {
+                    entry[key] = Value::from(text(&address[part]));
+                }
+            },
+            _ => custom.extend(field.to_custom_field()),
+        }
+    }
+    normalize_identity(&mut entry);
+    (EntryType::Identities, entry, custom)
+}
+
+// The file of a document item, stored in the export as files/<document id>__<file name>
+fn document_file(archive: &mut ZipArchive<Cursor<&[u8]>>, item: &Value, location: &str, preview: &mut ImportPreview) -> Option<(String, Vec<u8>)> {
+    let attributes = &item["details"]["documentAttributes"];
+    let name = text(&attributes["fileName"]);
+    let path = format!("files/{}__{}", text(&attributes["documentId"]), name);
+    let Ok(mut file) = archive.by_name(&path) else {
+        preview.skip(location, format!("Document {} is missing from the export", name));
+        return None;
+    };
+    if file.size() > MAX_ATTACHMENT_SIZE {
+        preview.skip(location, format!("Left out document {}: {}", name, AttachmentError::TooLarge(file.size())));
+        return None;
+    }
+    let mut content = Vec::new();
+    match file.read_to_end(&mut content) {
+        Ok(_) => Some((name, content)),
+        Err(e) => {
+            preview.skip(location, format!("Couldn't read document {}: {}", name, e));
+            None
+        },
+    }
+}
@@ -0,0 +1,201 @@
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::UNIX_EPOCH;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::import::{ImportError, ImportFormat, ImportPreview};
+use crate::vault::custom_field::totp_uri;
+use crate::vault::entry::new_entry;
+
+// Arguments added after the configured command, the file to decrypt comes last
+const DECRYPT_ARGS: [&str; 3] = ["--batch", "--quiet", "--decrypt"];
+
+// Keys of "key: value" lines that fill an entry field, anything else stays in the notes
+const USERNAME_KEYS: [&str; 4] = ["login", "username", "user", "email"];
+const URL_KEYS: [&str; 4] = ["url", "website", "site", "link"];
+const OTP_KEYS: [&str; 2] = ["otp", "totp"];
+
+// Where pass keeps its store when no directory is given
+fn default_store() -> PathBuf {
+    match env::var_os("PASSWORD_STORE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME").unwrap_or_default()).join(".password-store"),
+    }
+}
+
+// .gpg files below the directory, in name order. Hidden directories like .git and
+// .extensions are pass' own and skipped.
+fn find_password_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), ImportError> {
+    let mut children: Vec<PathBuf> = fs::read_dir(dir)?.filter_map(|child| child.ok()).map(|child| child.path()).collect();
+    children.sort();
+    for child in children {
+        let hidden = child.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if child.is_dir() && !hidden {
+            find_password_files(&child, files)?;
+        } else if child.extension().is_some_and(|extension| extension == "gpg") {
+            files.push(child);
+        }
+    }
+    Ok(())
+}
+
+// Run the gpg command on one file. The command may carry its own arguments,
+// e.g. "gpg2 --pinentry-mode loopback", and prints the plain text on stdout.
+fn decrypt(gpg_command: &str, file: &Path) -> Result<Result<String, String>, ImportError> {
+    let mut words = gpg_command.split_whitespace();
+    let program = words.next().unwrap_or("gpg");
+    let output = Command::new(program)
+        .args(words)
+        .args(DECRYPT_ARGS)
+        .arg(file)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| ImportError::Gpg(program.to_string(), e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("gpg couldn't decrypt the file");
+        return Ok(Err(reason.to_string()));
+    }
+    Ok(String::from_utf8(output.stdout).map_err(|_| String::from("The decrypted file isn't UTF-8 text")))
+}
+
+// Login from a decrypted pass file: the password on the first line, then "key: value"
+// lines and free text. Bare otpauth:// lines are what pass-otp writes.
+fn to_login(text: &str, title: &str, folder: Option<u64>) -> Value {
+    let mut lines = text.lines();
+    let mut entry = new_entry(EntryType::Passwords, folder);
+    entry["title"] = Value::from(title);
+    entry["password"] = Value::from(lines.next().unwrap_or(""));
+    let mut notes = Vec::new();
+    for line in lines {
+        if line.trim().starts_with("otpauth://") && entry["otpauth"] == "" {
+            entry["otpauth"] = Value::from(line.trim());
+            continue;
+        }
+        let field = line.split_once(':').and_then(|(key, value)| {
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+            match key.as_str() {
+                key if USERNAME_KEYS.contains(&key) && entry["username"] == "" => Some(("username", value.to_string())),
+                key if URL_KEYS.contains(&key) && entry["url"] == "" => Some(("url", value.to_string())),
+                key if OTP_KEYS.contains(&key) && entry["otpauth"] == "" => Some(("otpauth", totp_uri(value))),
+                _ => None,
+            }
+        });
+        match field {
+            Some((key, value)) if !value.is_empty() => entry[key] = Value::from(value),
+            _ => notes.push(line),
+        }
+    }
+    entry["notes"] = Value::from(notes.join("\n").trim());
+    entry
+}
+
+// Logins of a password-store directory, each .gpg file decrypted with the gpg command.
+// Subdirectories become folders and file names titles.
+pub fn parse_password_store(path: &str, gpg_command: &str, folder: Option<u64>) -> Result<ImportPreview, ImportError> {
+    let store = match path.trim() {
+        "" => default_store(),
+        path => PathBuf::from(path),
+    };
+    if !store.is_dir() {
+        return Err(ImportError::UnrecognizedFormat("a password-store directory"));
+    }
+    let mut files = Vec::new();
+    find_password_files(&store, &mut files)?;
+    if files.is_empty() {
+        return Err(ImportError::UnrecognizedFormat("a password-store directory with .gpg files"));
+    }
+
+    let mut preview = ImportPreview::new(ImportFormat::PassStore);
+    for file in files {
+        let relative = file.strip_prefix(&store).unwrap_or(&file).with_extension("");
+        let location = relative.to_string_lossy().to_string();
+        let text = match decrypt(gpg_command, &file)? {
+            Ok(text) if text.trim().is_empty() => {
+                preview.skip(location, "The file is empty");
+                continue;
+            },
+            Ok(text) => text,
+            Err(reason) => {
+                preview.skip(location, reason);
+                continue;
+            },
+        };
+        let title = relative.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let mut entry = to_login(&text, &title, folder);
+        // Files are rewritten on every change, their time is as close to "modified" as pass gets
+        if let Some(modified) = fs::metadata(&file).and_then(|metadata| metadata.modified()).ok().and_then(|time| time.duration_since(UNIX_EPOCH).ok()) {
+            entry["modified"] = Value::from(modified.as_secs());
+        }
+        let imported = preview.add(EntryType::Passwords, entry);
+        imported.folder_path = relative
+            .parent()
+            .into_iter()
+            .flat_map(|parent| parent.components())
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+    }
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Stand-in for gpg: prints the file it is given, which holds plain text, and fails like
+    // gpg does for files named broken.gpg
+    const FAKE_GPG: &str = "for file; do :; done\ncase \"$file\" in *broken.gpg) echo \"gpg: decryption failed: No secret key\" >&2; exit 2;; esac\ncat \"$file\"\n";
+
+    #[test]
+    fn password_store() {
+        let dir = env::temp_dir().join(format!("keybolt-pass-test-{}", std::process::id()));
+        let store = dir.join("store");
+        fs::create_dir_all(store.join("Email")).unwrap();
+        fs::create_dir_all(store.join(".git")).unwrap();
+        fs::write(dir.join("fake-gpg.sh"), FAKE_GPG).unwrap();
+        fs::write(store.join("Email/mail.example.com.gpg"), "s3cret\nlogin: bob\nurl: https://mail.example.com\notpauth://totp/mail?secret=JBSWY3DPEHPK3PXP\nrecovery codes below\n").unwrap();
+        fs::write(store.join("shop.gpg"), "  pw with spaces \nuser: al\nwebsite: https://shop.example.com\notp: JBSWY3DPEHPK3PXP\n").unwrap();
+        fs::write(store.join("broken.gpg"), "not decrypted").unwrap();
+        fs::write(store.join("empty.gpg"), "").unwrap();
+        fs::write(store.join(".git/hidden.gpg"), "skipped").unwrap();
+
+        let gpg_command = format!("sh {}", dir.join("fake-gpg.sh").display());
+        let preview = parse_password_store(&store.to_string_lossy(), &gpg_command, Some(3));
+        fs::remove_dir_all(&dir).unwrap();
+        let preview = preview.unwrap();
+
+        assert_eq!(preview.entries.len(), 2);
+        let mail = preview.entries.iter().find(|imported| imported.entry["title"] == "mail.example.com").unwrap();
+        assert_eq!(mail.folder_path, ["Email"]);
+        assert_eq!(mail.entry["password"], "s3cret");
+        assert_eq!(mail.entry["username"], "bob");
+        assert_eq!(mail.entry["url"], "https://mail.example.com");
+        assert_eq!(mail.entry["otpauth"], "otpauth://totp/mail?secret=JBSWY3DPEHPK3PXP");
+        assert_eq!(mail.entry["notes"], "recovery codes below");
+        assert_eq!(mail.entry["folder"], 3);
+
+        let shop = preview.entries.iter().find(|imported| imported.entry["title"] == "shop").unwrap();
+        assert!(shop.folder_path.is_empty());
+        assert_eq!(shop.entry["password"], "  pw with spaces ");
+        assert_eq!(shop.entry["username"], "al");
+        assert_eq!(shop.entry["url"], "https://shop.example.com");
+        assert_eq!(shop.entry["otpauth"], totp_uri("JBSWY3DPEHPK3PXP"));
+
+        let skipped: Vec<(&str, &str)> = preview.skipped.iter().map(|row| (row.location.as_str(), row.reason.as_str())).collect();
+        assert_eq!(skipped, [("broken", "gpg: decryption failed: No secret key"), ("empty", "The file is empty")]);
+    }
+
+    #[test]
+    fn missing_gpg_command() {
+        let dir = env::temp_dir().join(format!("keybolt-pass-missing-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("entry.gpg"), "pw").unwrap();
+        let result = parse_password_store(&dir.to_string_lossy(), "keybolt-no-such-gpg", None);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(result, Err(ImportError::Gpg(..))));
+    }
+}
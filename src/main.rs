@@ -3,6 +3,8 @@ use iced::window::{self, PlatformSpecific, Position};
 
 mod secure;
 mod gui;
+mod import;
+mod vault;
 use gui::core::app::KeyboltApp;
 use gui::styles::style_constants::FONT_SIZE_BODY;
 
@@ -0,0 +1,203 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::gui::pages::details_page::EntryType;
+use crate::secure::encrypt::{decrypt, derive_key, encrypt, generate_salt, EncryptError};
+
+// Encrypted blobs live next to the vault, one file per distinct content
+const ATTACHMENTS_DIR: &str = "attachments";
+
+// Largest single file that can be attached
+pub const MAX_ATTACHMENT_SIZE: u64 = 10 * 1024 * 1024;
+// Largest total size of the files attached to one entry
+pub const MAX_ENTRY_ATTACHMENTS_SIZE: u64 = 25 * 1024 * 1024;
+
+// Blob layout, as for the vault file: ciphertext, nonce, salt
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+/// A file attached to an entry, the content is stored as a separate encrypted blob
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attachment {
+    /// Keyed hash of the content, also the name of the blob
+    pub id: String,
+    pub name: String,
+    pub size: u64,
+    pub added: u64,
+}
+
+pub fn attachments(entry: &Value) -> Vec<Attachment> {
+    serde_json::from_value(entry["attachments"].clone()).unwrap_or_default()
+}
+
+pub fn set_attachments(entry: &mut Value, attachments: &[Attachment]) {
+    entry["attachments"] = serde_json::to_value(attachments).unwrap_or(Value::Array(Vec::new()));
+}
+
+// Encrypt a file into the blob store and reference it from the entry
+pub fn add_attachment(passphrase: &str, vault: &mut Value, entry: &mut Value, path: &str, added: u64) -> Result<Attachment, AttachmentError> {
+    let path = Path::new(path.trim());
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_file() {
+        return Err(AttachmentError::NotAFile);
+    }
+    // Checked before reading so oversized files are never loaded
+    if metadata.len() > MAX_ATTACHMENT_SIZE {
+        return Err(AttachmentError::TooLarge(metadata.len()));
+    }
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_else(|| String::from("attachment"));
+    store_attachment(passphrase, vault, entry, &name, &fs::read(path)?, added)
+}
+
+// Encrypt content into the blob store and reference it from the entry under the given name.
+// Identical files share one blob, whichever entries they're attached to.
+pub fn store_attachment(passphrase: &str, vault: &mut Value, entry: &mut Value, name: &str, content: &[u8], added: u64) -> Result<Attachment, AttachmentError> {
+    let size = content.len() as u64;
+    if size > MAX_ATTACHMENT_SIZE {
+        return Err(AttachmentError::TooLarge(size));
+    }
+    let mut current = attachments(entry);
+    let total: u64 = current.iter().map(|attachment| attachment.size).sum();
+    if total + size > MAX_ENTRY_ATTACHMENTS_SIZE {
+        return Err(AttachmentError::EntryFull);
+    }
+
+    let id = content_id(passphrase, vault, content);
+    let blob_path = blob_path(&id);
+    if !blob_path.exists() {
+        fs::create_dir_all(ATTACHMENTS_DIR)?;
+        let salt = generate_salt();
+        let key = derive_key(passphrase, &salt);
+        let (ciphertext, nonce, salt) = encrypt(&key.borrow(), content, &salt)?;
+        fs::write(&blob_path, [ciphertext, nonce, salt].concat())?;
+    }
+
+    let attachment = Attachment { id, name: name.to_string(), size, added };
+    current.push(attachment.clone());
+    set_attachments(entry, &current);
+    Ok(attachment)
+}
+
+// Decrypted content of an attachment
+pub fn read_attachment(passphrase: &str, attachment: &Attachment) -> Result<Vec<u8>, AttachmentError> {
+    let blob = fs::read(blob_path(&attachment.id)).map_err(|_| AttachmentError::Missing)?;
+    if blob.len() < NONCE_LEN + SALT_LEN {
+        return Err(AttachmentError::Missing);
+    }
+    let (ciphertext, rest) = blob.split_at(blob.len() - NONCE_LEN - SALT_LEN);
+    let (nonce, salt) = rest.split_at(NONCE_LEN);
+    let key = derive_key(passphrase, salt);
+    let content = decrypt(&key.borrow(), nonce, ciphertext)?;
+    Ok(content)
+}
+
+// Decrypt an attachment to a file, directories get a file named after the attachment
+pub fn save_attachment(passphrase: &str, attachment: &Attachment, destination: &str) -> Result<PathBuf, AttachmentError> {
+    let mut destination = PathBuf::from(destination.trim());
+    if destination.is_dir() {
+        destination.push(&attachment.name);
+    }
+    fs::write(&destination, read_attachment(passphrase, attachment)?)?;
+    Ok(destination)
+}
+
+// Delete blobs no entry refers to anymore, only run against the vault just read from disk
+pub fn prune_attachments(vault: &Value) -> Result<usize, AttachmentError> {
+    let referenced: BTreeSet<String> = EntryType::ALL
+        .iter()
+        .flat_map(|entry_type| vault[entry_type.as_str()].as_array().cloned().unwrap_or_default())
+        .flat_map(|entry| attachments(&entry))
+        .map(|attachment| attachment.id)
+        .collect();
+
+    let dir = match fs::read_dir(ATTACHMENTS_DIR) {
+        Ok(dir) => dir,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let mut removed = 0;
+    for file in dir {
+        let file = file?;
+        if !referenced.contains(&file.file_name().to_string_lossy().to_string()) {
+            fs::remove_file(file.path())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+// "1.2 MB" style sizes for the attachment list
+pub fn format_size(size: u64) -> String {
+    match size {
+        0..=1023 => format!("{} B", size),
+        1024..=1_048_575 => format!("{:.1} KB", size as f64 / 1024.0),
+        _ => format!("{:.1} MB", size as f64 / 1_048_576.0),
+    }
+}
+
+fn blob_path(id: &str) -> PathBuf {
+    Path::new(ATTACHMENTS_DIR).join(id)
+}
+
+// HMAC of the content under a key derived from the passphrase, so equal files share
+// a blob without the blob names revealing which well-known files are stored
+fn content_id(passphrase: &str, vault: &mut Value, content: &[u8]) -> String {
+    let salt = match vault["attachment_salt"].as_str().and_then(|salt| general_purpose::STANDARD.decode(salt).ok()) {
+        Some(salt) => salt,
+        None => {
+            let salt = generate_salt().to_vec();
+            vault["attachment_salt"] = Value::from(general_purpose::STANDARD.encode(&salt));
+            salt
+        },
+    };
+    let key = derive_key(passphrase, &salt);
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key.borrow()[..]).expect("HMAC accepts any key length");
+    mac.update(content);
+    mac.finalize().into_bytes().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Errors raised when adding, saving or pruning attachments
+#[derive(Debug)]
+pub enum AttachmentError {
+    Io(io::Error),
+    Encrypt(EncryptError),
+    NotAFile,
+    TooLarge(u64),
+    EntryFull,
+    Missing,
+}
+
+impl From<io::Error> for AttachmentError {
+    fn from(err: io::Error) -> AttachmentError {
+        AttachmentError::Io(err)
+    }
+}
+
+impl From<EncryptError> for AttachmentError {
+    fn from(err: EncryptError) -> AttachmentError {
+        AttachmentError::Encrypt(err)
+    }
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for AttachmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttachmentError::Io(e) => write!(f, "IO error: {}", e),
+            AttachmentError::Encrypt(e) => write!(f, "{}", e),
+            AttachmentError::NotAFile => write!(f, "Only files can be attached"),
+            AttachmentError::TooLarge(size) => write!(f, "{} is over the {} limit per file", format_size(*size), format_size(MAX_ATTACHMENT_SIZE)),
+            AttachmentError::EntryFull => write!(f, "Attachments of an entry can't exceed {} in total", format_size(MAX_ENTRY_ATTACHMENTS_SIZE)),
+            AttachmentError::Missing => write!(f, "The encrypted file is missing or damaged"),
+        }
+    }
+}
@@ -13,27 +13,55 @@ use sodiumoxide::utils;
 use std::fmt;
 use std::fs::File;
 use std::io;
+use std::path::Path;
 use std::io::{BufWriter, Read, Write};
 use std::string::FromUtf8Error;
 
 use crate::gui::pages::details_page::EntryType;
+use crate::vault::custom_field::{update_custom_fields, CustomFieldType};
+use crate::vault::document::update_documents;
+use crate::vault::schema;
+
+// Location of the encrypted vault
+const VAULT_PATH: &str = "encrypted_data.bin";
 
 pub fn encrypt_sensitive_fields(passphrase: &str, data: &mut Value, entry_type: EntryType) -> Result<(), EncryptError> {
-    match entry_type {
-        EntryType::Passwords => {
-            data["password"] = encrypt_field(passphrase, data["password"].as_str().unwrap_or(""));
+    // Sensitive fields are marked as secret in the entry type's schema
+    for spec in schema::fields(entry_type).iter().filter(|spec| spec.kind.is_secret()) {
+        data[spec.key] = encrypt_field(passphrase, data[spec.key].as_str().unwrap_or(""));
+    }
+    // Hidden custom fields are sensitive whatever the entry type
+    update_custom_fields(data, |fields| {
+        for field in fields.iter_mut().filter(|field| field.field_type == CustomFieldType::Hidden) {
+            field.value = encrypt_field(passphrase, &field.value).as_str().unwrap_or("").to_string();
         }
-        _ => ()
+    });
+    // Document numbers of identities
+    if entry_type == EntryType::Identities {
+        update_documents(data, |documents| {
+            for document in documents.iter_mut() {
+                document.number = encrypt_field(passphrase, &document.number).as_str().unwrap_or("").to_string();
+            }
+        });
     }
     Ok(())
 }
 
 pub fn decrypt_sensitive_fields(passphrase: &str, data: &mut Value, entry_type: EntryType) -> Result<(), EncryptError> {
-    match entry_type {
-        EntryType::Passwords => {
-            data["password"] = decrypt_field(passphrase, data["password"].as_str().unwrap_or(""));
+    for spec in schema::fields(entry_type).iter().filter(|spec| spec.kind.is_secret()) {
+        data[spec.key] = decrypt_field(passphrase, data[spec.key].as_str().unwrap_or(""));
+    }
+    update_custom_fields(data, |fields| {
+        for field in fields.iter_mut().filter(|field| field.field_type == CustomFieldType::Hidden) {
+            field.value = decrypt_field(passphrase, &field.value).as_str().unwrap_or("").to_string();
         }
-        _ => ()
+    });
+    if entry_type == EntryType::Identities {
+        update_documents(data, |documents| {
+            for document in documents.iter_mut() {
+                document.number = decrypt_field(passphrase, &document.number).as_str().unwrap_or("").to_string();
+            }
+        });
     }
     Ok(())
 }
@@ -66,6 +94,10 @@ fn encrypt_field(passphrase: &str, password_str: &str) -> Value {
 }
 
 fn decrypt_field(passphrase: &str, password_str: &str) -> Value {
+    // Entries created in the app start out with nothing encrypted yet
+    if password_str.is_empty() {
+        return json!("");
+    }
     let salt_offset = password_str.len() - &password_str[2..4].parse::<usize>().unwrap();
     let nonce_offset = salt_offset - &password_str[0..2].parse::<usize>().unwrap();
 
@@ -80,8 +112,13 @@ fn decrypt_field(passphrase: &str, password_str: &str) -> Value {
     return json!(String::from_utf8(decrypted_password).unwrap());
 }
 
+// Whether a vault has been created yet, otherwise the user is asked to create one
+pub fn vault_exists() -> bool {
+    Path::new(VAULT_PATH).exists()
+}
+
 pub fn read_data(passphrase: &str) -> Result<Value, EncryptError> {
-    let mut file = File::open("encrypted_data.bin")?;
+    let mut file = File::open(VAULT_PATH)?;
     let mut buffer = Vec::new();
     file.read_to_end(&mut buffer).unwrap();
 
@@ -120,7 +157,7 @@ pub fn write_data(passphrase: &str, data: Value) -> Result<(), EncryptError> {
     match encrypt_result {
         Ok((ciphertext, nonce, salt)) => {
             // Write the ciphertext, nonce, and salt to a file
-            let file = File::create("encrypted_data.bin")?;
+            let file = File::create(VAULT_PATH)?;
             let mut buf_writer = BufWriter::new(file);
             buf_writer.write_all(&ciphertext).unwrap();
             buf_writer.write_all(&nonce).unwrap();
@@ -0,0 +1,165 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// EFF large wordlist (7776 words, "<dice roll>\t<word>" per line), embedded at compile time
+const WORDLIST: &str = include_str!("../../resources/wordlists/eff_large_wordlist.txt");
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.<>/?~|";
+// Characters that are easily confused with each other when read or typed
+const AMBIGUOUS: &str = "Il1|O0o";
+
+pub const MIN_PASSWORD_LENGTH: u8 = 4;
+pub const MAX_PASSWORD_LENGTH: u8 = 128;
+pub const MIN_WORD_COUNT: u8 = 3;
+pub const MAX_WORD_COUNT: u8 = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeneratorMode {
+    Password,
+    Passphrase,
+}
+
+/// Options used by the password generator, shared by the generator page and the details pane
+#[derive(Clone, Debug)]
+pub struct GeneratorOptions {
+    pub mode: GeneratorMode,
+
+    // Random character password options
+    pub length: u8,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    pub exclude_ambiguous: bool,
+    pub require_each_class: bool,
+
+    // Diceware passphrase options
+    pub word_count: u8,
+    pub separator: String,
+    pub capitalize: bool,
+    pub include_number: bool,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            mode: GeneratorMode::Password,
+            length: 20,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: false,
+            require_each_class: true,
+            word_count: 6,
+            separator: String::from("-"),
+            capitalize: false,
+            include_number: false,
+        }
+    }
+}
+
+// Generate a password or passphrase depending on the selected mode
+pub fn generate(options: &GeneratorOptions) -> String {
+    match options.mode {
+        GeneratorMode::Password => generate_password(options),
+        GeneratorMode::Passphrase => generate_passphrase(options),
+    }
+}
+
+// Generate a random character password.
+// thread_rng is a CSPRNG (ChaCha seeded from the OS) and gen_range/choose sample without modulo bias.
+pub fn generate_password(options: &GeneratorOptions) -> String {
+    let classes = character_classes(options);
+    let pool: Vec<char> = classes.iter().flatten().copied().collect();
+    let length = options.length.clamp(MIN_PASSWORD_LENGTH, MAX_PASSWORD_LENGTH) as usize;
+    let mut rng = rand::thread_rng();
+
+    let mut password: Vec<char> = Vec::with_capacity(length);
+    // Guarantee one character from every enabled class, the rest comes from the whole pool
+    if options.require_each_class {
+        for class in &classes {
+            password.push(*class.choose(&mut rng).unwrap());
+        }
+    }
+    while password.len() < length {
+        password.push(*pool.choose(&mut rng).unwrap());
+    }
+    password.shuffle(&mut rng);
+
+    password.into_iter().collect()
+}
+
+// Generate a diceware-style passphrase from the embedded EFF wordlist
+pub fn generate_passphrase(options: &GeneratorOptions) -> String {
+    let words = wordlist();
+    let word_count = options.word_count.clamp(MIN_WORD_COUNT, MAX_WORD_COUNT) as usize;
+    let mut rng = rand::thread_rng();
+
+    let mut chosen: Vec<String> = (0..word_count)
+        .map(|_| {
+            let word = words.choose(&mut rng).unwrap();
+            if options.capitalize {
+                capitalize(word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect();
+
+    if options.include_number {
+        let index = rng.gen_range(0..chosen.len());
+        let digit = rng.gen_range(0..10);
+        chosen[index].push_str(&digit.to_string());
+    }
+
+    chosen.join(&options.separator)
+}
+
+// List of words from the embedded wordlist, dropping the dice roll column
+pub fn wordlist() -> Vec<&'static str> {
+    WORDLIST
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .collect()
+}
+
+// Enabled character classes, with ambiguous characters removed if requested.
+// Falls back to lowercase letters if every class has been disabled.
+fn character_classes(options: &GeneratorOptions) -> Vec<Vec<char>> {
+    let mut sets = Vec::new();
+    if options.lowercase {
+        sets.push(LOWERCASE);
+    }
+    if options.uppercase {
+        sets.push(UPPERCASE);
+    }
+    if options.digits {
+        sets.push(DIGITS);
+    }
+    if options.symbols {
+        sets.push(SYMBOLS);
+    }
+    if sets.is_empty() {
+        sets.push(LOWERCASE);
+    }
+
+    sets.into_iter()
+        .map(|set| {
+            set.chars()
+                .filter(|c| !options.exclude_ambiguous || !AMBIGUOUS.contains(*c))
+                .collect()
+        })
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
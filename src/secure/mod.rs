@@ -1 +1,6 @@
-pub mod encrypt;
\ No newline at end of file
+pub mod attachment;
+pub mod encrypt;
+pub mod generator;
+pub mod ssh;
+pub mod strength;
+pub mod totp;
\ No newline at end of file
@@ -0,0 +1,99 @@
+use rand::rngs::OsRng;
+use ssh_key::{Algorithm, HashAlg, LineEnding, PrivateKey, PublicKey};
+use std::fmt;
+use std::fs;
+use std::io;
+
+/// Fields of an SSH key entry derived from a private key
+pub struct SshKeyMaterial {
+    /// OpenSSH private key, unencrypted here, the vault encrypts it as a sensitive field
+    pub private_key: String,
+    pub public_key: String,
+    pub fingerprint: String,
+    pub comment: String,
+}
+
+// Generate a new Ed25519 key pair
+pub fn generate_ed25519(comment: &str) -> Result<SshKeyMaterial, SshError> {
+    let mut key = PrivateKey::random(&mut OsRng, Algorithm::Ed25519)?;
+    key.set_comment(comment);
+    key_material(&key)
+}
+
+// Import an OpenSSH private key, either pasted as text or read from a file path.
+// Passphrase protected keys are decrypted so they can be stored like any other secret.
+pub fn import_private_key(source: &str, passphrase: &str) -> Result<SshKeyMaterial, SshError> {
+    let source = source.trim();
+    let pem = if source.starts_with("-----BEGIN") {
+        source.to_string()
+    } else {
+        fs::read_to_string(source)?
+    };
+
+    let key = PrivateKey::from_openssh(pem)?;
+    let key = if key.is_encrypted() {
+        if passphrase.is_empty() {
+            return Err(SshError::PassphraseRequired);
+        }
+        key.decrypt(passphrase).map_err(|_| SshError::WrongPassphrase)?
+    } else {
+        key
+    };
+    key_material(&key)
+}
+
+// Public key line with a new comment, e.g. "ssh-ed25519 AAAA... alice@laptop"
+pub fn with_comment(public_key: &str, comment: &str) -> Result<String, SshError> {
+    let mut key = PublicKey::from_openssh(public_key.trim())?;
+    key.set_comment(comment);
+    Ok(key.to_openssh()?)
+}
+
+// Write the public key to a file, as an authorized_keys compatible line
+pub fn export_public_key(public_key: &str, path: &str) -> Result<(), SshError> {
+    let key = PublicKey::from_openssh(public_key.trim())?;
+    fs::write(path.trim(), format!("{}\n", key.to_openssh()?))?;
+    Ok(())
+}
+
+fn key_material(key: &PrivateKey) -> Result<SshKeyMaterial, SshError> {
+    Ok(SshKeyMaterial {
+        private_key: key.to_openssh(LineEnding::LF)?.to_string(),
+        public_key: key.public_key().to_openssh()?,
+        fingerprint: key.fingerprint(HashAlg::Sha256).to_string(),
+        comment: key.comment().to_string(),
+    })
+}
+
+// Errors raised when generating, importing or exporting SSH keys
+#[derive(Debug)]
+pub enum SshError {
+    Key(ssh_key::Error),
+    Io(io::Error),
+    PassphraseRequired,
+    WrongPassphrase,
+}
+
+impl From<ssh_key::Error> for SshError {
+    fn from(err: ssh_key::Error) -> SshError {
+        SshError::Key(err)
+    }
+}
+
+impl From<io::Error> for SshError {
+    fn from(err: io::Error) -> SshError {
+        SshError::Io(err)
+    }
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for SshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SshError::Key(e) => write!(f, "Invalid SSH key: {}", e),
+            SshError::Io(e) => write!(f, "IO error: {}", e),
+            SshError::PassphraseRequired => write!(f, "This key is protected, enter its passphrase"),
+            SshError::WrongPassphrase => write!(f, "Wrong passphrase for this key"),
+        }
+    }
+}
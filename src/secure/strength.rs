@@ -0,0 +1,410 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use crate::secure::generator::wordlist;
+
+// Frequently leaked passwords, matched against the whole (lowercased) password
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456", "password", "12345678", "qwerty", "123456789", "12345", "1234", "111111",
+    "1234567", "dragon", "123123", "baseball", "abc123", "football", "monkey", "letmein",
+    "696969", "shadow", "master", "666666", "qwertyuiop", "123321", "mustang", "1234567890",
+    "michael", "654321", "superman", "1qaz2wsx", "7777777", "121212", "000000", "qazwsx",
+    "123qwe", "killer", "trustno1", "jordan", "jennifer", "zxcvbnm", "asdfgh", "hunter",
+    "buster", "soccer", "harley", "batman", "andrew", "tigger", "sunshine", "iloveyou",
+    "2000", "charlie", "robert", "thomas", "hockey", "ranger", "daniel", "starwars",
+    "klaster", "112233", "george", "computer", "michelle", "jessica", "pepper", "1111",
+    "zxcvbn", "555555", "11111111", "131313", "freedom", "777777", "pass", "maggie",
+    "159753", "aaaaaa", "ginger", "princess", "joshua", "cheese", "amanda", "summer",
+    "love", "ashley", "nicole", "chelsea", "biteme", "matthew", "access", "yankees",
+    "987654321", "dallas", "austin", "thunder", "taylor", "matrix", "admin", "welcome",
+    "login", "passw0rd", "password1", "password123", "qwerty123", "changeme", "secret",
+    "p@ssw0rd", "letmein1", "welcome1", "iloveyou1", "monkey1", "dragon1", "keybolt",
+];
+
+// Keyboard rows used to detect walks such as "qwerty" or "asdf"
+const KEYBOARD_ROWS: &[&str] = &[
+    "`1234567890-=",
+    "qwertyuiop[]\\",
+    "asdfghjkl;'",
+    "zxcvbnm,./",
+];
+
+// Shifted number row, mapped back onto the unshifted keys
+const SHIFTED_ROW: &str = "~!@#$%^&*()_+";
+
+const MIN_PATTERN_LENGTH: usize = 3;
+const MIN_WORD_LENGTH: usize = 4;
+const MAX_WORD_LENGTH: usize = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PatternKind {
+    CommonPassword,
+    Dictionary,
+    KeyboardWalk,
+    Sequence,
+    Repeat,
+    Date,
+}
+
+// A pattern found in a password, with the entropy an attacker needs to guess it
+#[derive(Clone, Debug)]
+struct PatternMatch {
+    kind: PatternKind,
+    start: usize,
+    end: usize,
+    bits: f64,
+}
+
+/// Result of estimating a password's strength
+#[derive(Clone, Debug)]
+pub struct StrengthEstimate {
+    pub entropy_bits: f64,
+    /// 0 (very weak) to 4 (very strong)
+    pub score: u8,
+    pub warnings: Vec<String>,
+    pub suggestions: Vec<String>,
+}
+
+impl StrengthEstimate {
+    pub fn label(&self) -> &'static str {
+        match self.score {
+            0 => "Very weak",
+            1 => "Weak",
+            2 => "Fair",
+            3 => "Strong",
+            _ => "Very strong",
+        }
+    }
+}
+
+// Estimate the strength of a password by splitting it into guessable patterns
+// (common passwords, dictionary words, keyboard walks, sequences, repeats, dates)
+// and charging brute-force entropy only for the characters left over.
+pub fn estimate(password: &str) -> StrengthEstimate {
+    let chars: Vec<char> = password.chars().collect();
+    if chars.is_empty() {
+        return StrengthEstimate {
+            entropy_bits: 0.0,
+            score: 0,
+            warnings: Vec::new(),
+            suggestions: vec![String::from("Enter a password")],
+        };
+    }
+
+    let mut matches = Vec::new();
+    let mut entropy_bits = 0.0;
+
+    if let Some(rank) = COMMON_PASSWORDS.iter().position(|p| *p == password.to_lowercase()) {
+        let common = PatternMatch {
+            kind: PatternKind::CommonPassword,
+            start: 0,
+            end: chars.len(),
+            bits: ((rank + 1) as f64).log2(),
+        };
+        entropy_bits = common.bits;
+        matches.push(common);
+    } else {
+        let pool_bits = (pool_size(&chars) as f64).log2();
+        let mut i = 0;
+        while i < chars.len() {
+            match best_match(&chars, i) {
+                Some(found) => {
+                    entropy_bits += found.bits;
+                    i = found.end;
+                    matches.push(found);
+                },
+                None => {
+                    entropy_bits += pool_bits;
+                    i += 1;
+                },
+            }
+        }
+    }
+
+    let score = score_for(entropy_bits);
+    let (warnings, suggestions) = feedback(&chars, &matches, score);
+
+    StrengthEstimate {
+        entropy_bits,
+        score,
+        warnings,
+        suggestions,
+    }
+}
+
+fn score_for(entropy_bits: f64) -> u8 {
+    match entropy_bits {
+        b if b < 28.0 => 0,
+        b if b < 36.0 => 1,
+        b if b < 60.0 => 2,
+        b if b < 80.0 => 3,
+        _ => 4,
+    }
+}
+
+// Number of possible characters an attacker has to try, based on the classes used
+fn pool_size(chars: &[char]) -> u32 {
+    let mut pool = 0;
+    if chars.iter().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if chars.iter().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if chars.iter().any(|c| c.is_ascii_punctuation() || *c == ' ') {
+        pool += 33;
+    }
+    if chars.iter().any(|c| !c.is_ascii()) {
+        pool += 100;
+    }
+    pool.max(1)
+}
+
+// Longest (then cheapest) pattern starting at position i
+fn best_match(chars: &[char], i: usize) -> Option<PatternMatch> {
+    [
+        match_dictionary(chars, i),
+        match_date(chars, i),
+        match_repeat(chars, i),
+        match_sequence(chars, i),
+        match_keyboard_walk(chars, i),
+    ]
+    .into_iter()
+    .flatten()
+    .max_by(|a, b| {
+        (a.end - a.start)
+            .cmp(&(b.end - b.start))
+            .then(b.bits.total_cmp(&a.bits))
+    })
+}
+
+fn dictionary() -> &'static HashSet<&'static str> {
+    static DICTIONARY: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    DICTIONARY.get_or_init(|| wordlist().into_iter().collect())
+}
+
+// Undo common leet-speak substitutions so "p4ssw0rd" is matched as "password"
+fn unleet(c: char) -> char {
+    match c {
+        '4' | '@' => 'a',
+        '3' => 'e',
+        '1' | '!' => 'i',
+        '0' => 'o',
+        '5' | '$' => 's',
+        '7' => 't',
+        _ => c.to_ascii_lowercase(),
+    }
+}
+
+fn match_dictionary(chars: &[char], i: usize) -> Option<PatternMatch> {
+    let words = dictionary();
+    let longest = MAX_WORD_LENGTH.min(chars.len() - i);
+    (MIN_WORD_LENGTH..=longest).rev().find_map(|len| {
+        let slice = &chars[i..i + len];
+        let word: String = slice.iter().map(|c| unleet(*c)).collect();
+        let common_rank = COMMON_PASSWORDS.iter().position(|p| *p == word);
+        if !words.contains(word.as_str()) && common_rank.is_none() {
+            return None;
+        }
+        let mut bits = match common_rank {
+            Some(rank) => ((rank + 1) as f64).log2(),
+            None => (words.len() as f64).log2(),
+        };
+        // Capitalization and substitutions only add a few guesses each
+        if slice.iter().any(|c| c.is_ascii_uppercase()) {
+            bits += 1.0;
+        }
+        if slice.iter().any(|c| unleet(*c) != c.to_ascii_lowercase()) {
+            bits += 1.0;
+        }
+        Some(PatternMatch { kind: PatternKind::Dictionary, start: i, end: i + len, bits })
+    })
+}
+
+fn key_position(c: char) -> Option<(usize, usize)> {
+    let c = c.to_ascii_lowercase();
+    if let Some(col) = SHIFTED_ROW.find(c) {
+        return Some((0, col));
+    }
+    KEYBOARD_ROWS
+        .iter()
+        .enumerate()
+        .find_map(|(row, keys)| keys.find(c).map(|col| (row, col)))
+}
+
+// Rows are staggered, so a key touches col-1/col+1 on its row, col/col+1 above and col-1/col below
+fn keys_adjacent(a: char, b: char) -> bool {
+    match (key_position(a), key_position(b)) {
+        (Some((ra, ca)), Some((rb, cb))) => {
+            let (ra, ca, rb, cb) = (ra as i32, ca as i32, rb as i32, cb as i32);
+            match rb - ra {
+                0 => (cb - ca).abs() == 1,
+                -1 => cb == ca || cb == ca + 1,
+                1 => cb == ca || cb == ca - 1,
+                _ => false,
+            }
+        },
+        _ => false,
+    }
+}
+
+fn match_keyboard_walk(chars: &[char], i: usize) -> Option<PatternMatch> {
+    let mut end = i + 1;
+    while end < chars.len() && keys_adjacent(chars[end - 1], chars[end]) {
+        end += 1;
+    }
+    let len = end - i;
+    if len < MIN_PATTERN_LENGTH {
+        return None;
+    }
+    // Any starting key, then roughly two likely directions for every following key
+    let bits = 47f64.log2() + (len - 1) as f64;
+    Some(PatternMatch { kind: PatternKind::KeyboardWalk, start: i, end, bits })
+}
+
+// Runs like "abcd", "6543" where every character is one step from the previous one
+fn match_sequence(chars: &[char], i: usize) -> Option<PatternMatch> {
+    if i + 1 >= chars.len() {
+        return None;
+    }
+    let step = chars[i + 1] as i32 - chars[i] as i32;
+    if step.abs() != 1 || !chars[i].is_ascii_alphanumeric() {
+        return None;
+    }
+    let mut end = i + 2;
+    while end < chars.len() && chars[end] as i32 - chars[end - 1] as i32 == step && chars[end].is_ascii_alphanumeric() {
+        end += 1;
+    }
+    let len = end - i;
+    if len < MIN_PATTERN_LENGTH {
+        return None;
+    }
+    let alphabet: f64 = if chars[i].is_ascii_digit() { 10.0 } else { 26.0 };
+    let bits = alphabet.log2() + (len as f64).log2() + 1.0;
+    Some(PatternMatch { kind: PatternKind::Sequence, start: i, end, bits })
+}
+
+// Repeated characters ("aaaa") or repeated blocks ("abcabc")
+fn match_repeat(chars: &[char], i: usize) -> Option<PatternMatch> {
+    let remaining = chars.len() - i;
+    (1..=remaining / 2)
+        .filter_map(|block| {
+            let mut count = 1;
+            while i + (count + 1) * block <= chars.len()
+                && chars[i..i + block] == chars[i + count * block..i + (count + 1) * block]
+            {
+                count += 1;
+            }
+            let len = count * block;
+            if count < 2 || len < MIN_PATTERN_LENGTH {
+                return None;
+            }
+            let block_bits = block as f64 * (pool_size(&chars[i..i + block]) as f64).log2();
+            let bits = block_bits + (count as f64).log2();
+            Some(PatternMatch { kind: PatternKind::Repeat, start: i, end: i + len, bits })
+        })
+        .max_by_key(|found| found.end)
+}
+
+fn is_valid_day_month(day: u32, month: u32) -> bool {
+    (1..=31).contains(&day) && (1..=12).contains(&month)
+}
+
+fn is_year(year: u32) -> bool {
+    (1900..=2099).contains(&year)
+}
+
+// Dates such as "1987", "12/05/1987", "05-12-87", "19871205" or "120587"
+fn match_date(chars: &[char], i: usize) -> Option<PatternMatch> {
+    let candidate: String = chars[i..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '/' | '-' | '.'))
+        .take(10)
+        .collect();
+
+    let longest = (MIN_PATTERN_LENGTH..=candidate.len()).rev().find(|len| {
+        let text = &candidate[..*len];
+        let parts: Vec<&str> = text.split(['/', '-', '.']).collect();
+        if parts.len() == 3 {
+            if parts.iter().any(|p| p.is_empty() || p.len() > 4) {
+                return false;
+            }
+            let nums: Vec<u32> = parts.iter().filter_map(|p| p.parse().ok()).collect();
+            if nums.len() != 3 {
+                return false;
+            }
+            let year_first = parts[0].len() == 4 && is_year(nums[0]) && is_valid_day_month(nums[2], nums[1]);
+            let year_last = (parts[2].len() == 2 || (parts[2].len() == 4 && is_year(nums[2])))
+                && (is_valid_day_month(nums[0], nums[1]) || is_valid_day_month(nums[1], nums[0]));
+            return year_first || year_last;
+        }
+        if parts.len() != 1 || !text.chars().all(|c| c.is_ascii_digit()) {
+            return false;
+        }
+        let num = |range: std::ops::Range<usize>| text[range].parse::<u32>().unwrap_or(0);
+        match text.len() {
+            4 => is_year(num(0..4)),
+            6 => is_valid_day_month(num(0..2), num(2..4)) || is_valid_day_month(num(2..4), num(0..2)),
+            8 => {
+                (is_year(num(0..4)) && is_valid_day_month(num(6..8), num(4..6)))
+                    || (is_year(num(4..8))
+                        && (is_valid_day_month(num(0..2), num(2..4)) || is_valid_day_month(num(2..4), num(0..2))))
+            },
+            _ => false,
+        }
+    })?;
+
+    // A year on its own is ~200 guesses, a full date ~200 years of 365 days
+    let bits = if longest == 4 { 200f64.log2() } else { (200.0f64 * 365.0).log2() };
+    Some(PatternMatch { kind: PatternKind::Date, start: i, end: i + longest, bits })
+}
+
+fn feedback(chars: &[char], matches: &[PatternMatch], score: u8) -> (Vec<String>, Vec<String>) {
+    let mut warnings = Vec::new();
+    let mut suggestions = Vec::new();
+    let has = |kind: PatternKind| matches.iter().any(|m| m.kind == kind);
+
+    if has(PatternKind::CommonPassword) {
+        warnings.push(String::from("This is a commonly used password"));
+    }
+    if has(PatternKind::Dictionary) {
+        warnings.push(String::from("Contains dictionary words"));
+        suggestions.push(String::from("Substitutions like '@' for 'a' don't help much"));
+    }
+    if has(PatternKind::KeyboardWalk) {
+        warnings.push(String::from("Contains a keyboard pattern"));
+        suggestions.push(String::from("Avoid runs of neighbouring keys"));
+    }
+    if has(PatternKind::Sequence) {
+        warnings.push(String::from("Contains a sequence like 'abc' or '123'"));
+    }
+    if has(PatternKind::Repeat) {
+        warnings.push(String::from("Contains repeated characters"));
+        suggestions.push(String::from("Avoid repeated words and characters"));
+    }
+    if has(PatternKind::Date) {
+        warnings.push(String::from("Contains a date or year"));
+        suggestions.push(String::from("Avoid dates and years that are associated with you"));
+    }
+
+    if score < 3 {
+        if chars.len() < 12 {
+            suggestions.push(String::from("Use at least 12 characters, or a passphrase of several words"));
+        }
+        let classes = [
+            chars.iter().any(|c| c.is_ascii_lowercase()),
+            chars.iter().any(|c| c.is_ascii_uppercase()),
+            chars.iter().any(|c| c.is_ascii_digit()),
+            chars.iter().any(|c| !c.is_ascii_alphanumeric()),
+        ];
+        if classes.iter().filter(|present| **present).count() < 3 {
+            suggestions.push(String::from("Mix uppercase letters, digits and symbols"));
+        }
+        suggestions.push(String::from("Try the password generator"));
+    }
+
+    (warnings, suggestions)
+}
@@ -0,0 +1,216 @@
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::fmt;
+
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OtpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OtpKind {
+    // Time based, a new code every `period` seconds
+    Totp { period: u64 },
+    // Counter based, a new code every time the counter is incremented
+    Hotp { counter: u64 },
+}
+
+/// Parameters of an `otpauth://` URI
+#[derive(Clone, Debug)]
+pub struct OtpAuth {
+    pub kind: OtpKind,
+    pub secret: Vec<u8>,
+    pub digits: u32,
+    pub algorithm: OtpAlgorithm,
+    pub issuer: String,
+    pub account: String,
+}
+
+impl OtpAuth {
+    // Parse an otpauth URI, e.g. otpauth://totp/Example:alice@example.com?secret=JBSWY3DPEHPK3PXP&issuer=Example
+    pub fn parse(uri: &str) -> Result<OtpAuth, OtpError> {
+        let rest = uri.trim().strip_prefix("otpauth://").ok_or(OtpError::InvalidUri)?;
+        let (otp_type, rest) = rest.split_once('/').ok_or(OtpError::InvalidUri)?;
+        let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let label = percent_decode(label);
+        let (mut issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (issuer.to_string(), account.trim().to_string()),
+            None => (String::new(), label.clone()),
+        };
+
+        let mut secret = None;
+        let mut digits = 6;
+        let mut algorithm = OtpAlgorithm::Sha1;
+        let mut period = 30;
+        let mut counter = None;
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            let value = percent_decode(value);
+            match key.to_ascii_lowercase().as_str() {
+                "secret" => secret = Some(base32_decode(&value)?),
+                "digits" => {
+                    digits = value.parse().map_err(|_| OtpError::InvalidParameter(String::from("digits")))?;
+                    if !(6..=8).contains(&digits) {
+                        return Err(OtpError::InvalidParameter(String::from("digits")));
+                    }
+                },
+                "algorithm" => {
+                    algorithm = match value.to_ascii_uppercase().as_str() {
+                        "SHA1" => OtpAlgorithm::Sha1,
+                        "SHA256" => OtpAlgorithm::Sha256,
+                        "SHA512" => OtpAlgorithm::Sha512,
+                        _ => return Err(OtpError::InvalidParameter(String::from("algorithm"))),
+                    }
+                },
+                "period" => {
+                    period = value.parse().map_err(|_| OtpError::InvalidParameter(String::from("period")))?;
+                    if period == 0 {
+                        return Err(OtpError::InvalidParameter(String::from("period")));
+                    }
+                },
+                "counter" => counter = Some(value.parse().map_err(|_| OtpError::InvalidParameter(String::from("counter")))?),
+                // The issuer parameter takes precedence over the label prefix
+                "issuer" => issuer = value,
+                _ => (),
+            }
+        }
+
+        let kind = match otp_type.to_ascii_lowercase().as_str() {
+            "totp" => OtpKind::Totp { period },
+            "hotp" => OtpKind::Hotp { counter: counter.ok_or(OtpError::InvalidParameter(String::from("counter")))? },
+            other => return Err(OtpError::UnsupportedType(other.to_string())),
+        };
+
+        let secret = secret.ok_or(OtpError::MissingSecret)?;
+        if secret.is_empty() {
+            return Err(OtpError::MissingSecret);
+        }
+
+        Ok(OtpAuth { kind, secret, digits, algorithm, issuer, account })
+    }
+
+    // Code for the given moving factor (RFC 4226)
+    pub fn code_for_counter(&self, counter: u64) -> String {
+        let message = counter.to_be_bytes();
+        let hash = match self.algorithm {
+            OtpAlgorithm::Sha1 => hmac_digest::<Hmac<Sha1>>(&self.secret, &message),
+            OtpAlgorithm::Sha256 => hmac_digest::<Hmac<Sha256>>(&self.secret, &message),
+            OtpAlgorithm::Sha512 => hmac_digest::<Hmac<Sha512>>(&self.secret, &message),
+        };
+
+        // Dynamic truncation
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([hash[offset] & 0x7f, hash[offset + 1], hash[offset + 2], hash[offset + 3]]);
+        let code = binary % 10u32.pow(self.digits);
+        format!("{:0width$}", code, width = self.digits as usize)
+    }
+
+    // Current code and the seconds left before it changes (RFC 6238).
+    // HOTP codes don't expire, so no countdown is returned for them.
+    pub fn current_code(&self, unix_time: u64) -> (String, Option<u64>) {
+        match self.kind {
+            OtpKind::Totp { period } => {
+                let code = self.code_for_counter(unix_time / period);
+                (code, Some(period - unix_time % period))
+            },
+            OtpKind::Hotp { counter } => (self.code_for_counter(counter), None),
+        }
+    }
+}
+
+// Rewrite the counter parameter of an HOTP URI, keeping every other parameter untouched
+pub fn with_counter(uri: &str, counter: u64) -> String {
+    let (base, query) = uri.split_once('?').unwrap_or((uri, ""));
+    let mut params: Vec<String> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !pair.to_ascii_lowercase().starts_with("counter="))
+        .map(|pair| pair.to_string())
+        .collect();
+    params.push(format!("counter={}", counter));
+    format!("{}?{}", base, params.join("&"))
+}
+
+// Split a code in two halves for readability, "123456" -> "123 456"
+pub fn format_code(code: &str) -> String {
+    let middle = code.len() / 2;
+    format!("{} {}", &code[..middle], &code[middle..])
+}
+
+fn hmac_digest<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as hmac::digest::KeyInit>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+// RFC 4648 base32, ignoring case, padding, spaces and dashes that people add when copying secrets
+fn base32_decode(input: &str) -> Result<Vec<u8>, OtpError> {
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut output = Vec::new();
+    for c in input.chars().filter(|c| !matches!(c, '=' | ' ' | '-')) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a as char == c.to_ascii_uppercase())
+            .ok_or(OtpError::InvalidSecret)?;
+        bits = (bits << 5) | value as u64;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            output.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    Ok(output)
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok()).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                output.push(byte);
+                i += 3;
+            },
+            (b'+', _) => {
+                output.push(b' ');
+                i += 1;
+            },
+            (byte, _) => {
+                output.push(byte);
+                i += 1;
+            },
+        }
+    }
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+// Errors raised when parsing otpauth URIs
+#[derive(Debug)]
+pub enum OtpError {
+    InvalidUri,
+    UnsupportedType(String),
+    MissingSecret,
+    InvalidSecret,
+    InvalidParameter(String),
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for OtpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OtpError::InvalidUri => write!(f, "Not an otpauth:// URI"),
+            OtpError::UnsupportedType(t) => write!(f, "Unsupported OTP type: {}", t),
+            OtpError::MissingSecret => write!(f, "Missing secret"),
+            OtpError::InvalidSecret => write!(f, "Secret is not valid base32"),
+            OtpError::InvalidParameter(p) => write!(f, "Invalid {} parameter", p),
+        }
+    }
+}
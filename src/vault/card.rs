@@ -0,0 +1,228 @@
+use serde_json::Value;
+use std::fmt;
+
+use crate::vault::date::days_in_month;
+
+/// Card network, detected from the issuer identification number (IIN) at the start of the number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardBrand {
+    Visa,
+    Mastercard,
+    Amex,
+    Discover,
+    DinersClub,
+    Jcb,
+    UnionPay,
+    Maestro,
+}
+
+impl CardBrand {
+    // Number lengths issued by the network
+    fn lengths(&self) -> &'static [usize] {
+        match self {
+            CardBrand::Visa => &[13, 16, 19],
+            CardBrand::Mastercard => &[16],
+            CardBrand::Amex => &[15],
+            CardBrand::Discover | CardBrand::Jcb | CardBrand::UnionPay => &[16, 17, 18, 19],
+            CardBrand::DinersClub => &[14, 15, 16, 17, 18, 19],
+            CardBrand::Maestro => &[12, 13, 14, 15, 16, 17, 18, 19],
+        }
+    }
+
+    // Amex prints a four digit code on the front, everyone else three on the back
+    pub fn security_code_length(&self) -> usize {
+        match self {
+            CardBrand::Amex => 4,
+            _ => 3,
+        }
+    }
+}
+
+impl fmt::Display for CardBrand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            CardBrand::Visa => "Visa",
+            CardBrand::Mastercard => "Mastercard",
+            CardBrand::Amex => "Amex",
+            CardBrand::Discover => "Discover",
+            CardBrand::DinersClub => "Diners Club",
+            CardBrand::Jcb => "JCB",
+            CardBrand::UnionPay => "UnionPay",
+            CardBrand::Maestro => "Maestro",
+        })
+    }
+}
+
+// Card numbers are usually written in groups separated by spaces or dashes
+fn digits(number: &str) -> String {
+    number.chars().filter(|c| !c.is_whitespace() && *c != '-').collect()
+}
+
+// First `len` digits of the number as an integer, for range checks
+fn prefix(digits: &str, len: usize) -> Option<u32> {
+    digits.get(..len)?.parse().ok()
+}
+
+// Brand from the IIN ranges, more specific ranges are checked before broader ones
+pub fn detect_brand(number: &str) -> Option<CardBrand> {
+    let digits = digits(number);
+    let in_range = |len: usize, low: u32, high: u32| prefix(&digits, len).is_some_and(|p| (low..=high).contains(&p));
+
+    if in_range(2, 34, 34) || in_range(2, 37, 37) {
+        Some(CardBrand::Amex)
+    } else if in_range(1, 4, 4) {
+        Some(CardBrand::Visa)
+    } else if in_range(2, 51, 55) || in_range(4, 2221, 2720) {
+        Some(CardBrand::Mastercard)
+    } else if in_range(4, 6011, 6011) || in_range(3, 644, 649) || in_range(2, 65, 65) || in_range(6, 622126, 622925) {
+        Some(CardBrand::Discover)
+    } else if in_range(4, 3528, 3589) {
+        Some(CardBrand::Jcb)
+    } else if in_range(3, 300, 305) || in_range(2, 36, 36) || in_range(2, 38, 39) {
+        Some(CardBrand::DinersClub)
+    } else if in_range(2, 62, 62) {
+        Some(CardBrand::UnionPay)
+    } else if in_range(2, 50, 50) || in_range(2, 56, 58) || in_range(4, 6304, 6304) || in_range(4, 6759, 6759) || in_range(6, 676770, 676770) || in_range(6, 676774, 676774) {
+        Some(CardBrand::Maestro)
+    } else {
+        None
+    }
+}
+
+// Luhn mod 10 check: double every second digit from the right
+fn luhn_valid(digits: &str) -> bool {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, digit)| match (i % 2 == 1, digit * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => digit,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+// Card number: digits only, a length issued by its brand and a valid Luhn checksum
+pub fn validate_card_number(number: &str) -> Result<(), CardFieldError> {
+    let digits = digits(number);
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(CardFieldError::InvalidCharacters);
+    }
+    let length_ok = match detect_brand(&digits) {
+        Some(brand) => brand.lengths().contains(&digits.len()),
+        None => (12..=19).contains(&digits.len()),
+    };
+    if !length_ok {
+        return Err(CardFieldError::InvalidLength);
+    }
+    if !luhn_valid(&digits) {
+        return Err(CardFieldError::ChecksumMismatch);
+    }
+    Ok(())
+}
+
+// Last four digits, shown in lists instead of the full number
+pub fn last_four(number: &str) -> String {
+    let digits: Vec<char> = digits(number).chars().filter(|c| c.is_ascii_digit()).collect();
+    digits[digits.len().saturating_sub(4)..].iter().collect()
+}
+
+// Number in the groups printed on the card, 4-6-5 for Amex and groups of four otherwise
+pub fn format_card_number(number: &str) -> String {
+    let digits = digits(number);
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return number.to_string();
+    }
+    let groups: &[usize] = match detect_brand(&digits) {
+        Some(CardBrand::Amex) => &[4, 6, 5],
+        _ => &[4, 4, 4, 4, 3],
+    };
+    let mut formatted = Vec::new();
+    let mut rest = digits.as_str();
+    for size in groups {
+        if rest.is_empty() {
+            break;
+        }
+        let (group, remainder) = rest.split_at((*size).min(rest.len()));
+        formatted.push(group);
+        rest = remainder;
+    }
+    if !rest.is_empty() {
+        formatted.push(rest);
+    }
+    formatted.join(" ")
+}
+
+// Expiry as (month, year), from "MM/YY" or the older "MM-YYYY" and "MM/YYYY" forms
+pub fn parse_expiry(expiry: &str) -> Option<(u32, i64)> {
+    let (month, year) = expiry.trim().split_once(['/', '-'])?;
+    let month: u32 = month.trim().parse().ok()?;
+    let year_digits = year.trim();
+    let year: i64 = year_digits.parse().ok()?;
+    let year = match year_digits.len() {
+        2 => 2000 + year,
+        4 => year,
+        _ => return None,
+    };
+    (1..=12).contains(&month).then_some((month, year))
+}
+
+// Cards are valid through the last day of their expiry month
+pub fn expires_on(expiry: &str) -> Option<String> {
+    let (month, year) = parse_expiry(expiry)?;
+    Some(format!("{:04}-{:02}-{:02}", year, month, days_in_month(year, month)))
+}
+
+// Expiry in the "MM/YY" form printed on cards
+pub fn validate_expiry(expiry: &str) -> Result<(), CardFieldError> {
+    parse_expiry(expiry).map(|_| ()).ok_or(CardFieldError::InvalidExpiry)
+}
+
+// Security code length depends on the brand, unknown brands accept either
+pub fn validate_security_code(code: &str, brand: Option<CardBrand>) -> Result<(), CardFieldError> {
+    let code = code.trim();
+    if !code.chars().all(|c| c.is_ascii_digit()) {
+        return Err(CardFieldError::InvalidCharacters);
+    }
+    let length_ok = match brand {
+        Some(brand) => code.len() == brand.security_code_length(),
+        None => code.len() == 3 || code.len() == 4,
+    };
+    if !length_ok {
+        return Err(CardFieldError::InvalidLength);
+    }
+    Ok(())
+}
+
+// Keep the derived last four in step with the number, and store expiry dates as "MM/YY"
+pub fn normalize_card(entry: &mut Value) {
+    let number = entry["card_number"].as_str().unwrap_or("").to_string();
+    entry["card_last_four"] = Value::String(last_four(&number));
+    if let Some((month, year)) = entry["expiration_date"].as_str().and_then(parse_expiry) {
+        entry["expiration_date"] = Value::String(format!("{:02}/{:02}", month, year % 100));
+    }
+}
+
+// Errors raised when validating card fields
+#[derive(Debug)]
+pub enum CardFieldError {
+    InvalidCharacters,
+    InvalidLength,
+    ChecksumMismatch,
+    InvalidExpiry,
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for CardFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardFieldError::InvalidCharacters => write!(f, "Contains invalid characters"),
+            CardFieldError::InvalidLength => write!(f, "Has the wrong length for this card"),
+            CardFieldError::ChecksumMismatch => write!(f, "Check digit doesn't match, look for a typo"),
+            CardFieldError::InvalidExpiry => write!(f, "Use the MM/YY format"),
+        }
+    }
+}
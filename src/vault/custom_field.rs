@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+use crate::vault::date::parse_date;
+use crate::vault::identity::is_email;
+
+/// Kind of value a custom field holds, decides how it's shown and validated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    /// Stored encrypted like the other sensitive fields
+    Hidden,
+    Url,
+    Email,
+    Date,
+    /// otpauth URI or bare base32 secret, shown as a live code
+    Totp,
+}
+
+impl CustomFieldType {
+    pub const ALL: [CustomFieldType; 6] = [
+        CustomFieldType::Text,
+        CustomFieldType::Hidden,
+        CustomFieldType::Url,
+        CustomFieldType::Email,
+        CustomFieldType::Date,
+        CustomFieldType::Totp,
+    ];
+}
+
+impl fmt::Display for CustomFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            CustomFieldType::Text => "Text",
+            CustomFieldType::Hidden => "Hidden",
+            CustomFieldType::Url => "URL",
+            CustomFieldType::Email => "Email",
+            CustomFieldType::Date => "Date",
+            CustomFieldType::Totp => "TOTP",
+        })
+    }
+}
+
+/// A user-defined field, stored in order in the entry's "custom_fields" array
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomField {
+    pub label: String,
+    #[serde(rename = "type")]
+    pub field_type: CustomFieldType,
+    pub value: String,
+}
+
+pub fn custom_fields(entry: &Value) -> Vec<CustomField> {
+    serde_json::from_value(entry["custom_fields"].clone()).unwrap_or_default()
+}
+
+pub fn set_custom_fields(entry: &mut Value, fields: &[CustomField]) {
+    entry["custom_fields"] = serde_json::to_value(fields).unwrap_or(Value::Array(Vec::new()));
+}
+
+// Apply a change to the custom fields of an entry
+pub fn update_custom_fields(entry: &mut Value, change: impl FnOnce(&mut Vec<CustomField>)) {
+    let mut fields = custom_fields(entry);
+    change(&mut fields);
+    set_custom_fields(entry, &fields);
+}
+
+// Swap a field with its neighbour, `up` moves it towards the top
+pub fn move_custom_field(fields: &mut [CustomField], index: usize, up: bool) {
+    let other = if up { index.checked_sub(1) } else { Some(index + 1) };
+    if let Some(other) = other.filter(|other| *other < fields.len() && index < fields.len()) {
+        fields.swap(index, other);
+    }
+}
+
+// otpauth URI for a TOTP field, bare secrets get the default parameters
+pub fn totp_uri(value: &str) -> String {
+    let value = value.trim();
+    if value.starts_with("otpauth://") {
+        value.to_string()
+    } else {
+        format!("otpauth://totp/?secret={}", value.replace(' ', ""))
+    }
+}
+
+// Reason a value doesn't fit its field type, empty values are always accepted
+pub fn validation_error(field: &CustomField) -> Option<&'static str> {
+    let value = field.value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    match field.field_type {
+        CustomFieldType::Url if !(value.starts_with("https://") || value.starts_with("http://")) || value.contains(' ') => {
+            Some("URLs start with http:// or https://")
+        },
+        CustomFieldType::Email if !is_email(value) => Some("Not a valid email address"),
+        CustomFieldType::Date if parse_date(value).is_none() => Some("Use the YYYY-MM-DD format"),
+        _ => None,
+    }
+}
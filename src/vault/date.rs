@@ -0,0 +1,100 @@
+// Dates are stored as "YYYY-MM-DD" strings, days are counted in UTC
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// Items expiring within this many days get a warning
+pub const EXPIRY_WARNING_DAYS: i64 = 30;
+
+/// How close a date-limited item is to its expiry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExpiryStatus {
+    Valid,
+    ExpiringSoon { days_left: i64 },
+    Expired,
+}
+
+// Parse a "YYYY-MM-DD" date
+pub fn parse_date(date: &str) -> Option<(i64, u32, u32)> {
+    let mut parts = date.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01 (Howard Hinnant's days_from_civil)
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// "YYYY-MM-DD" of a unix time (Howard Hinnant's civil_from_days)
+pub fn format_date(unix_time: u64) -> String {
+    let days = (unix_time / SECONDS_PER_DAY) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Seconds since the epoch of an ISO 8601 timestamp like "2023-05-01T12:34:56.789Z",
+// as found in other apps' exports. Offsets other than Z are ignored.
+pub fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let (date, time) = timestamp.trim().split_once('T')?;
+    let (year, month, day) = parse_date(date)?;
+    let mut parts = time.get(..8)?.split(':').map(|part| part.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    u64::try_from(days_from_civil(year, month, day) * SECONDS_PER_DAY as i64 + hours * 3600 + minutes * 60 + seconds).ok()
+}
+
+// Days from today until the given date, negative once it has passed
+pub fn days_until(date: &str, unix_time: u64) -> Option<i64> {
+    let (year, month, day) = parse_date(date)?;
+    Some(days_from_civil(year, month, day) - (unix_time / SECONDS_PER_DAY) as i64)
+}
+
+// Expired after the expiry day itself, expiring soon within `warning_days` of it
+pub fn expiry_status(date: &str, unix_time: u64, warning_days: i64) -> Option<ExpiryStatus> {
+    let days_left = days_until(date, unix_time)?;
+    Some(if days_left < 0 {
+        ExpiryStatus::Expired
+    } else if days_left <= warning_days {
+        ExpiryStatus::ExpiringSoon { days_left }
+    } else {
+        ExpiryStatus::Valid
+    })
+}
+
+impl ExpiryStatus {
+    // Short warning shown next to an entry, nothing while it's still valid
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            ExpiryStatus::Valid => None,
+            ExpiryStatus::ExpiringSoon { days_left: 0 } => Some(String::from("Expires today")),
+            ExpiryStatus::ExpiringSoon { days_left: 1 } => Some(String::from("Expires tomorrow")),
+            ExpiryStatus::ExpiringSoon { days_left } => Some(format!("Expires in {} days", days_left)),
+            ExpiryStatus::Expired => Some(String::from("Expired")),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+use crate::vault::date::parse_date;
+use crate::vault::identity::find_country;
+
+/// Kind of identity document
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentType {
+    Passport,
+    DriversLicence,
+    NationalId,
+    ResidencePermit,
+    Other,
+}
+
+impl DocumentType {
+    pub const ALL: [DocumentType; 5] = [
+        DocumentType::Passport,
+        DocumentType::DriversLicence,
+        DocumentType::NationalId,
+        DocumentType::ResidencePermit,
+        DocumentType::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DocumentType::Passport => "Passport",
+            DocumentType::DriversLicence => "Driver's Licence",
+            DocumentType::NationalId => "National ID",
+            DocumentType::ResidencePermit => "Residence Permit",
+            DocumentType::Other => "Other Document",
+        }
+    }
+}
+
+impl fmt::Display for DocumentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// A document held by an identity, stored in order in the entry's "documents" array
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IdentityDocument {
+    #[serde(rename = "type")]
+    pub document_type: DocumentType,
+    /// Encrypted at rest like the other sensitive fields
+    pub number: String,
+    /// Country code or name, as for the identity's own country
+    #[serde(default)]
+    pub issuing_country: String,
+    #[serde(default)]
+    pub issuing_authority: String,
+    /// "YYYY-MM-DD" dates, empty when unknown or the document doesn't expire
+    #[serde(default)]
+    pub issued: String,
+    #[serde(default)]
+    pub expires: String,
+}
+
+/// Field of a document edited from the details pane
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocumentField {
+    Number,
+    IssuingCountry,
+    IssuingAuthority,
+    Issued,
+    Expires,
+}
+
+impl IdentityDocument {
+    pub fn new(document_type: DocumentType) -> IdentityDocument {
+        IdentityDocument {
+            document_type,
+            number: String::new(),
+            issuing_country: String::new(),
+            issuing_authority: String::new(),
+            issued: String::new(),
+            expires: String::new(),
+        }
+    }
+
+    pub fn set(&mut self, field: DocumentField, value: String) {
+        match field {
+            DocumentField::Number => self.number = value,
+            DocumentField::IssuingCountry => self.issuing_country = value,
+            DocumentField::IssuingAuthority => self.issuing_authority = value,
+            DocumentField::Issued => self.issued = value,
+            DocumentField::Expires => self.expires = value,
+        }
+    }
+
+    // Full country name when the code is known
+    pub fn issuing_country_name(&self) -> String {
+        match find_country(&self.issuing_country) {
+            Some(country) => country.name.to_string(),
+            None => self.issuing_country.trim().to_string(),
+        }
+    }
+
+    // Expiry as "YYYY-MM-DD", None when it isn't set or can't be read
+    pub fn expires_on(&self) -> Option<String> {
+        parse_date(&self.expires).map(|(year, month, day)| format!("{:04}-{:02}-{:02}", year, month, day))
+    }
+}
+
+pub fn documents(entry: &Value) -> Vec<IdentityDocument> {
+    serde_json::from_value(entry["documents"].clone()).unwrap_or_default()
+}
+
+pub fn set_documents(entry: &mut Value, documents: &[IdentityDocument]) {
+    entry["documents"] = serde_json::to_value(documents).unwrap_or(Value::Array(Vec::new()));
+}
+
+// Apply a change to the documents of an identity
+pub fn update_documents(entry: &mut Value, change: impl FnOnce(&mut Vec<IdentityDocument>)) {
+    let mut documents = documents(entry);
+    change(&mut documents);
+    set_documents(entry, &documents);
+}
+
+// Reason a document's dates can't be right, empty dates are always accepted
+pub fn validation_error(document: &IdentityDocument) -> Option<&'static str> {
+    let issued = parse_date(&document.issued);
+    let expires = parse_date(&document.expires);
+    if (!document.issued.trim().is_empty() && issued.is_none()) || (!document.expires.trim().is_empty() && expires.is_none()) {
+        return Some("Use the YYYY-MM-DD format for dates");
+    }
+    match (issued, expires) {
+        (Some(issued), Some(expires)) if issued > expires => Some("Expires before it was issued"),
+        _ => None,
+    }
+}
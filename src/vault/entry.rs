@@ -0,0 +1,129 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::gui::pages::details_page::EntryType;
+use crate::vault::card::normalize_card;
+use crate::vault::folder::{normalize_folders, set_entry_folder};
+use crate::vault::identity::normalize_identity;
+use crate::vault::schema;
+
+// Seconds since the unix epoch, used for entry timestamps
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Timestamp field of an entry, entries saved before timestamps existed count as 0
+pub fn timestamp(entry: &Value, field: &str) -> u64 {
+    entry[field].as_u64().unwrap_or(0)
+}
+
+// Record that an entry has been changed
+pub fn touch_modified(entry: &mut Value) {
+    entry["modified"] = Value::from(now());
+}
+
+// Record that an entry has been opened or copied from
+pub fn touch_used(entry: &mut Value) {
+    entry["last_used"] = Value::from(now());
+}
+
+// Blank entry of the given type, as created by the "New" button
+pub fn new_entry(entry_type: EntryType, folder: Option<u64>) -> Value {
+    let mut entry = json!({ "favorite": false, "tags": [], "created": now(), "modified": now() });
+    for spec in schema::fields(entry_type) {
+        entry[spec.key] = spec.default_value();
+    }
+    set_entry_folder(&mut entry, folder);
+    entry
+}
+
+// Lines of a multi-line text field, there is always at least one to edit
+pub fn field_lines(entry: &Value, field: &str) -> Vec<String> {
+    entry[field].as_str().unwrap_or("").split('\n').map(|line| line.to_string()).collect()
+}
+
+// Tags of an entry, accepting both a list and the older comma separated string
+pub fn entry_tags(entry: &Value) -> Vec<String> {
+    match &entry["tags"] {
+        Value::Array(tags) => tags
+            .iter()
+            .filter_map(|tag| tag.as_str())
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        Value::String(tags) => tags
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Favorites used to be stored as a "true"/"false" string
+pub fn is_favorite(entry: &Value) -> bool {
+    match &entry["favorite"] {
+        Value::Bool(favorite) => *favorite,
+        Value::String(favorite) => favorite.eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
+// Add a tag unless the entry already has it (ignoring case), returns whether it was added
+pub fn add_tag(entry: &mut Value, tag: &str) -> bool {
+    let tag = tag.trim();
+    let mut tags = entry_tags(entry);
+    if tag.is_empty() || tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+        return false;
+    }
+    tags.push(tag.to_string());
+    entry["tags"] = Value::from(tags);
+    true
+}
+
+pub fn remove_tag(entry: &mut Value, tag: &str) {
+    let tags: Vec<String> = entry_tags(entry).into_iter().filter(|t| t != tag).collect();
+    entry["tags"] = Value::from(tags);
+}
+
+// Every tag used in the vault with the number of entries carrying it
+pub fn all_tags(vault: &Value) -> BTreeMap<String, usize> {
+    let mut tags = BTreeMap::new();
+    for entry_type in EntryType::ALL {
+        for entry in vault[entry_type.as_str()].as_array().into_iter().flatten() {
+            for tag in entry_tags(entry) {
+                *tags.entry(tag).or_insert(0) += 1;
+            }
+        }
+    }
+    tags
+}
+
+// Bring entries saved by older versions up to the current format
+pub fn normalize_vault(vault: &mut Value) {
+    for entry_type in EntryType::ALL {
+        // Vaults created before an entry type existed don't have its array yet
+        if !vault[entry_type.as_str()].is_array() {
+            vault[entry_type.as_str()] = Value::Array(Vec::new());
+        }
+        if let Some(entries) = vault[entry_type.as_str()].as_array_mut() {
+            for entry in entries {
+                // Tags used to be a single free-text string
+                entry["tags"] = Value::from(entry_tags(entry));
+                entry["favorite"] = Value::Bool(is_favorite(entry));
+                // The last four used to be edited by hand and could drift from the number
+                if entry_type == EntryType::Cards {
+                    normalize_card(entry);
+                }
+                if entry_type == EntryType::Identities {
+                    normalize_identity(entry);
+                }
+            }
+        }
+    }
+    normalize_folders(vault);
+}
@@ -0,0 +1,71 @@
+use serde_json::Value;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::vault::date::{expiry_status, ExpiryStatus};
+use crate::vault::document::documents;
+use crate::vault::schema;
+use crate::vault::search::field_text;
+
+/// When expiry is checked from and how many days ahead entries get flagged
+#[derive(Clone, Copy, Debug)]
+pub struct ExpiryWindow {
+    pub current_time: u64,
+    pub warning_days: i64,
+}
+
+/// An entry with a date that has passed or falls within the warning window
+#[derive(Clone, Debug)]
+pub struct ExpiringEntry {
+    pub entry_type: EntryType,
+    /// Position of the entry in its array, as used by `Message::SelectEntryOfType`
+    pub index: usize,
+    pub title: String,
+    /// Label of the date field, e.g. "Expires" or "Expiration Date", or the document type
+    pub field: &'static str,
+    pub status: ExpiryStatus,
+}
+
+// Sort key, expired first and then by the days left
+fn urgency(status: &ExpiryStatus) -> i64 {
+    match status {
+        ExpiryStatus::Expired => i64::MIN,
+        ExpiryStatus::ExpiringSoon { days_left } => *days_left,
+        ExpiryStatus::Valid => i64::MAX,
+    }
+}
+
+// Most urgent of the entry's expiry fields and identity documents, labelled with the
+// field or document type, None when nothing needs attention
+pub fn entry_expiry(entry_type: EntryType, entry: &Value, window: ExpiryWindow) -> Option<(&'static str, ExpiryStatus)> {
+    let fields = schema::fields(entry_type)
+        .iter()
+        .filter_map(|spec| Some((spec.label, spec.expires_on(&field_text(&entry[spec.key]))?)));
+    let documents = documents(entry)
+        .into_iter()
+        .filter_map(|document| Some((document.document_type.label(), document.expires_on()?)));
+    fields
+        .chain(documents)
+        .filter_map(|(label, date)| Some((label, expiry_status(&date, window.current_time, window.warning_days)?)))
+        .filter(|(_, status)| *status != ExpiryStatus::Valid)
+        .min_by_key(|(_, status)| urgency(status))
+}
+
+// Every entry of the vault that has expired or is about to, the most urgent first
+pub fn expiring_entries(vault: &Value, window: ExpiryWindow) -> Vec<ExpiringEntry> {
+    let mut expiring: Vec<ExpiringEntry> = EntryType::ALL
+        .into_iter()
+        .flat_map(|entry_type| {
+            vault[entry_type.as_str()]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .filter_map(move |(index, entry)| {
+                    let (field, status) = entry_expiry(entry_type, entry, window)?;
+                    Some(ExpiringEntry { entry_type, index, title: field_text(&entry["title"]), field, status })
+                })
+        })
+        .collect();
+    expiring.sort_by_key(|expiring| urgency(&expiring.status));
+    expiring
+}
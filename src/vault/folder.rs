@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::gui::pages::details_page::EntryType;
+
+/// A folder entries can be placed in, stored in the vault's "folders" array.
+/// Folders are referenced by id so renaming or moving one never touches its entries.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: u64,
+    pub name: String,
+    /// Parent folder, None for top level folders
+    pub parent: Option<u64>,
+}
+
+/// A folder as shown in the folder tree, with its nesting depth
+#[derive(Clone, Debug)]
+pub struct FolderNode {
+    pub folder: Folder,
+    pub depth: usize,
+}
+
+/// Destination offered by the "Move to folder" menu
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FolderChoice {
+    pub id: Option<u64>,
+    pub path: String,
+}
+
+impl fmt::Display for FolderChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path)
+    }
+}
+
+pub fn folders(vault: &Value) -> Vec<Folder> {
+    serde_json::from_value(vault["folders"].clone()).unwrap_or_default()
+}
+
+fn save_folders(vault: &mut Value, folders: &[Folder]) {
+    vault["folders"] = serde_json::to_value(folders).unwrap_or(Value::Array(Vec::new()));
+}
+
+// Folder an entry is placed in, None when it isn't in any folder
+pub fn entry_folder(entry: &Value) -> Option<u64> {
+    entry["folder"].as_u64()
+}
+
+pub fn set_entry_folder(entry: &mut Value, folder: Option<u64>) {
+    entry["folder"] = folder.map(Value::from).unwrap_or(Value::Null);
+}
+
+// Folders in depth first order, siblings sorted by name
+pub fn folder_tree(vault: &Value) -> Vec<FolderNode> {
+    let mut all = folders(vault);
+    all.sort_by_key(|folder| folder.name.to_lowercase());
+    let mut tree = Vec::new();
+    add_children(&all, None, 0, &mut tree);
+    tree
+}
+
+fn add_children(all: &[Folder], parent: Option<u64>, depth: usize, tree: &mut Vec<FolderNode>) {
+    for folder in all.iter().filter(|folder| folder.parent == parent) {
+        tree.push(FolderNode { folder: folder.clone(), depth });
+        add_children(all, Some(folder.id), depth + 1, tree);
+    }
+}
+
+// Full path of a folder, e.g. "Work / Clients"
+pub fn folder_path(vault: &Value, id: u64) -> String {
+    let all = folders(vault);
+    let mut names = Vec::new();
+    let mut current = Some(id);
+    // Bounded by the folder count so a broken parent cycle can't loop forever
+    while let Some(folder) = current.and_then(|id| all.iter().find(|folder| folder.id == id)) {
+        if names.len() > all.len() {
+            break;
+        }
+        names.push(folder.name.clone());
+        current = folder.parent;
+    }
+    names.reverse();
+    names.join(" / ")
+}
+
+// Every destination for "Move to folder", starting with no folder at all
+pub fn folder_choices(vault: &Value) -> Vec<FolderChoice> {
+    let mut choices = vec![FolderChoice { id: None, path: String::from("No folder") }];
+    choices.extend(folder_tree(vault).into_iter().map(|node| FolderChoice {
+        id: Some(node.folder.id),
+        path: folder_path(vault, node.folder.id),
+    }));
+    choices
+}
+
+// A folder and all folders nested inside it
+pub fn folder_with_descendants(vault: &Value, id: u64) -> BTreeSet<u64> {
+    let all = folders(vault);
+    let mut found = BTreeSet::from([id]);
+    loop {
+        let before = found.len();
+        for folder in &all {
+            if folder.parent.is_some_and(|parent| found.contains(&parent)) {
+                found.insert(folder.id);
+            }
+        }
+        if found.len() == before {
+            return found;
+        }
+    }
+}
+
+// Create a folder and return its id
+pub fn create_folder(vault: &mut Value, name: &str, parent: Option<u64>) -> Result<u64, FolderError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(FolderError::EmptyName);
+    }
+    let mut all = folders(vault);
+    if all.iter().any(|folder| folder.parent == parent && folder.name.eq_ignore_ascii_case(name)) {
+        return Err(FolderError::DuplicateName(name.to_string()));
+    }
+    let id = all.iter().map(|folder| folder.id).max().map_or(1, |max| max + 1);
+    all.push(Folder { id, name: name.to_string(), parent });
+    save_folders(vault, &all);
+    Ok(id)
+}
+
+// Folder at a path of names below `parent`, creating the ones that don't exist yet.
+// Used by imports, which name folders rather than refer to them by id.
+pub fn folder_at_path(vault: &mut Value, parent: Option<u64>, path: &[String]) -> Option<u64> {
+    let mut current = parent;
+    for name in path.iter().map(|name| name.trim()).filter(|name| !name.is_empty()) {
+        let existing = folders(vault)
+            .into_iter()
+            .find(|folder| folder.parent == current && folder.name.eq_ignore_ascii_case(name))
+            .map(|folder| folder.id);
+        current = match existing {
+            Some(id) => Some(id),
+            None => create_folder(vault, name, current).ok(),
+        };
+    }
+    current
+}
+
+pub fn rename_folder(vault: &mut Value, id: u64, name: &str) -> Result<(), FolderError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(FolderError::EmptyName);
+    }
+    let mut all = folders(vault);
+    let parent = all.iter().find(|folder| folder.id == id).ok_or(FolderError::NotFound)?.parent;
+    if all.iter().any(|folder| folder.id != id && folder.parent == parent && folder.name.eq_ignore_ascii_case(name)) {
+        return Err(FolderError::DuplicateName(name.to_string()));
+    }
+    if let Some(folder) = all.iter_mut().find(|folder| folder.id == id) {
+        folder.name = name.to_string();
+    }
+    save_folders(vault, &all);
+    Ok(())
+}
+
+// Delete a folder and its subfolders.
+// Contained entries are either deleted too or moved up to the deleted folder's parent.
+pub fn delete_folder(vault: &mut Value, id: u64, delete_entries: bool) -> Result<(), FolderError> {
+    let all = folders(vault);
+    let parent = all.iter().find(|folder| folder.id == id).ok_or(FolderError::NotFound)?.parent;
+    let removed = folder_with_descendants(vault, id);
+
+    for entry_type in EntryType::ALL {
+        if let Some(entries) = vault[entry_type.as_str()].as_array_mut() {
+            if delete_entries {
+                entries.retain(|entry| !entry_folder(entry).is_some_and(|folder| removed.contains(&folder)));
+            } else {
+                for entry in entries.iter_mut().filter(|entry| entry_folder(entry).is_some_and(|folder| removed.contains(&folder))) {
+                    set_entry_folder(entry, parent);
+                }
+            }
+        }
+    }
+
+    let remaining: Vec<Folder> = all.into_iter().filter(|folder| !removed.contains(&folder.id)).collect();
+    save_folders(vault, &remaining);
+    Ok(())
+}
+
+// Number of entries in a folder, not counting its subfolders
+pub fn entry_count(vault: &Value, id: u64) -> usize {
+    EntryType::ALL
+        .into_iter()
+        .flat_map(|entry_type| vault[entry_type.as_str()].as_array().into_iter().flatten())
+        .filter(|entry| entry_folder(entry) == Some(id))
+        .count()
+}
+
+// Make sure the folder list exists and drop references to folders that are gone
+pub fn normalize_folders(vault: &mut Value) {
+    if !vault["folders"].is_array() {
+        vault["folders"] = Value::Array(Vec::new());
+    }
+    let ids: BTreeSet<u64> = folders(vault).iter().map(|folder| folder.id).collect();
+    for entry_type in EntryType::ALL {
+        if let Some(entries) = vault[entry_type.as_str()].as_array_mut() {
+            for entry in entries {
+                let folder = entry_folder(entry).filter(|id| ids.contains(id));
+                set_entry_folder(entry, folder);
+            }
+        }
+    }
+}
+
+// Errors raised when managing folders
+#[derive(Debug)]
+pub enum FolderError {
+    EmptyName,
+    DuplicateName(String),
+    NotFound,
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for FolderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FolderError::EmptyName => write!(f, "Folder name can't be empty"),
+            FolderError::DuplicateName(name) => write!(f, "A folder named {} already exists here", name),
+            FolderError::NotFound => write!(f, "Folder not found"),
+        }
+    }
+}
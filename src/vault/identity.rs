@@ -0,0 +1,404 @@
+use serde_json::Value;
+use std::fmt;
+
+/// How addresses are written in a country: field names, postal code formats and line order
+pub struct AddressFormat {
+    /// ISO 3166-1 alpha-2 code, the value stored in the entry
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Other ways the country is commonly written, matched when reading older entries
+    pub aliases: &'static [&'static str],
+    /// Country calling code and the trunk prefix dropped from national numbers
+    pub calling_code: &'static str,
+    pub trunk_prefix: &'static str,
+    /// Name of the state/province level, empty when addresses don't use one
+    pub region_label: &'static str,
+    /// Region codes accepted, any value is accepted when empty
+    pub regions: &'static [&'static str],
+    pub postal_label: &'static str,
+    /// 'N' is a digit, 'A' a letter, '?' either, anything else must match as is
+    pub postal_patterns: &'static [&'static str],
+    /// Address lines with {street}, {city}, {region}, {postal} and {country} placeholders
+    pub lines: &'static [&'static str],
+}
+
+const US_STATES: &[&str] = &[
+    "AL", "AK", "AZ", "AR", "CA", "CO", "CT", "DE", "FL", "GA", "HI", "ID", "IL", "IN", "IA", "KS", "KY",
+    "LA", "ME", "MD", "MA", "MI", "MN", "MS", "MO", "MT", "NE", "NV", "NH", "NJ", "NM", "NY", "NC", "ND",
+    "OH", "OK", "OR", "PA", "RI", "SC", "SD", "TN", "TX", "UT", "VT", "VA", "WA", "WV", "WI", "WY",
+    "DC", "AS", "GU", "MP", "PR", "VI",
+];
+const CA_PROVINCES: &[&str] = &["AB", "BC", "MB", "NB", "NL", "NS", "NT", "NU", "ON", "PE", "QC", "SK", "YT"];
+const AU_STATES: &[&str] = &["ACT", "NSW", "NT", "QLD", "SA", "TAS", "VIC", "WA"];
+
+// Most of continental Europe: postal code before the city and no region
+const POSTAL_CITY_LINES: &[&str] = &["{street}", "{postal} {city}", "{country}"];
+
+pub const ADDRESS_FORMATS: &[AddressFormat] = &[
+    AddressFormat {
+        code: "US", name: "United States", aliases: &["USA", "United States of America", "America"],
+        calling_code: "1", trunk_prefix: "1",
+        region_label: "State", regions: US_STATES,
+        postal_label: "ZIP Code", postal_patterns: &["NNNNN", "NNNNN-NNNN"],
+        lines: &["{street}", "{city}, {region} {postal}", "{country}"],
+    },
+    AddressFormat {
+        code: "CA", name: "Canada", aliases: &[],
+        calling_code: "1", trunk_prefix: "1",
+        region_label: "Province", regions: CA_PROVINCES,
+        postal_label: "Postal Code", postal_patterns: &["ANA NAN", "ANANAN"],
+        lines: &["{street}", "{city} {region} {postal}", "{country}"],
+    },
+    AddressFormat {
+        code: "GB", name: "United Kingdom", aliases: &["UK", "Great Britain", "England", "Scotland", "Wales", "Northern Ireland"],
+        calling_code: "44", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Postcode",
+        postal_patterns: &["AN NAA", "ANN NAA", "AAN NAA", "AANN NAA", "ANA NAA", "AANA NAA", "ANNAA", "ANNNAA", "AANNAA", "AANNNAA", "ANANAA", "AANANAA"],
+        lines: &["{street}", "{city}", "{postal}", "{country}"],
+    },
+    AddressFormat {
+        code: "IE", name: "Ireland", aliases: &["Eire"],
+        calling_code: "353", trunk_prefix: "0",
+        region_label: "County", regions: &[],
+        postal_label: "Eircode", postal_patterns: &["A?? ????", "A??????"],
+        lines: &["{street}", "{city}", "{region}", "{postal}", "{country}"],
+    },
+    AddressFormat {
+        code: "DE", name: "Germany", aliases: &["Deutschland"],
+        calling_code: "49", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Postleitzahl", postal_patterns: &["NNNNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "AT", name: "Austria", aliases: &["Österreich"],
+        calling_code: "43", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Postleitzahl", postal_patterns: &["NNNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "CH", name: "Switzerland", aliases: &["Schweiz", "Suisse"],
+        calling_code: "41", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Postal Code", postal_patterns: &["NNNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "FR", name: "France", aliases: &[],
+        calling_code: "33", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Code Postal", postal_patterns: &["NNNNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "BE", name: "Belgium", aliases: &["België", "Belgique"],
+        calling_code: "32", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Postal Code", postal_patterns: &["NNNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "NL", name: "Netherlands", aliases: &["Holland", "Nederland", "The Netherlands"],
+        calling_code: "31", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Postcode", postal_patterns: &["NNNN AA", "NNNNAA"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "IT", name: "Italy", aliases: &["Italia"],
+        // Italian numbers keep their leading zero internationally
+        calling_code: "39", trunk_prefix: "",
+        region_label: "Province", regions: &[],
+        postal_label: "CAP", postal_patterns: &["NNNNN"],
+        lines: &["{street}", "{postal} {city} {region}", "{country}"],
+    },
+    AddressFormat {
+        code: "ES", name: "Spain", aliases: &["España"],
+        calling_code: "34", trunk_prefix: "",
+        region_label: "Province", regions: &[],
+        postal_label: "Código Postal", postal_patterns: &["NNNNN"],
+        lines: &["{street}", "{postal} {city}", "{region}", "{country}"],
+    },
+    AddressFormat {
+        code: "SE", name: "Sweden", aliases: &["Sverige"],
+        calling_code: "46", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Postnummer", postal_patterns: &["NNN NN", "NNNNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "NO", name: "Norway", aliases: &["Norge"],
+        calling_code: "47", trunk_prefix: "",
+        region_label: "", regions: &[],
+        postal_label: "Postnummer", postal_patterns: &["NNNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "DK", name: "Denmark", aliases: &["Danmark"],
+        calling_code: "45", trunk_prefix: "",
+        region_label: "", regions: &[],
+        postal_label: "Postnummer", postal_patterns: &["NNNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "PL", name: "Poland", aliases: &["Polska"],
+        calling_code: "48", trunk_prefix: "",
+        region_label: "", regions: &[],
+        postal_label: "Kod Pocztowy", postal_patterns: &["NN-NNN"],
+        lines: POSTAL_CITY_LINES,
+    },
+    AddressFormat {
+        code: "AU", name: "Australia", aliases: &[],
+        calling_code: "61", trunk_prefix: "0",
+        region_label: "State", regions: AU_STATES,
+        postal_label: "Postcode", postal_patterns: &["NNNN"],
+        lines: &["{street}", "{city} {region} {postal}", "{country}"],
+    },
+    AddressFormat {
+        code: "NZ", name: "New Zealand", aliases: &[],
+        calling_code: "64", trunk_prefix: "0",
+        region_label: "", regions: &[],
+        postal_label: "Postcode", postal_patterns: &["NNNN"],
+        lines: &["{street}", "{city} {postal}", "{country}"],
+    },
+    AddressFormat {
+        code: "JP", name: "Japan", aliases: &["Nippon"],
+        calling_code: "81", trunk_prefix: "0",
+        region_label: "Prefecture", regions: &[],
+        postal_label: "Postal Code", postal_patterns: &["NNN-NNNN", "NNNNNNN"],
+        lines: &["{street}", "{city}, {region} {postal}", "{country}"],
+    },
+    AddressFormat {
+        code: "IN", name: "India", aliases: &["Bharat"],
+        calling_code: "91", trunk_prefix: "0",
+        region_label: "State", regions: &[],
+        postal_label: "PIN Code", postal_patterns: &["NNNNNN", "NNN NNN"],
+        lines: &["{street}", "{city} {postal}", "{region}", "{country}"],
+    },
+    AddressFormat {
+        code: "BR", name: "Brazil", aliases: &["Brasil"],
+        calling_code: "55", trunk_prefix: "0",
+        region_label: "State", regions: &[],
+        postal_label: "CEP", postal_patterns: &["NNNNN-NNN", "NNNNNNNN"],
+        lines: &["{street}", "{city} - {region}", "{postal}", "{country}"],
+    },
+    AddressFormat {
+        code: "MX", name: "Mexico", aliases: &["México"],
+        calling_code: "52", trunk_prefix: "",
+        region_label: "State", regions: &[],
+        postal_label: "Código Postal", postal_patterns: &["NNNNN"],
+        lines: &["{street}", "{postal} {city}, {region}", "{country}"],
+    },
+    AddressFormat {
+        code: "SG", name: "Singapore", aliases: &[],
+        calling_code: "65", trunk_prefix: "",
+        region_label: "", regions: &[],
+        postal_label: "Postal Code", postal_patterns: &["NNNNNN"],
+        lines: &["{street}", "{country} {postal}"],
+    },
+];
+
+// Used for countries without a known format, nothing is validated against it
+const GENERIC_FORMAT: AddressFormat = AddressFormat {
+    code: "", name: "", aliases: &[],
+    calling_code: "", trunk_prefix: "",
+    region_label: "Region", regions: &[],
+    postal_label: "Postal Code", postal_patterns: &[],
+    lines: &["{street}", "{postal} {city}", "{region}", "{country}"],
+};
+
+// Country by ISO code, name or a common alias, ignoring case
+pub fn find_country(country: &str) -> Option<&'static AddressFormat> {
+    let country = country.trim();
+    if country.is_empty() {
+        return None;
+    }
+    ADDRESS_FORMATS.iter().find(|format| {
+        format.code.eq_ignore_ascii_case(country)
+            || format.name.eq_ignore_ascii_case(country)
+            || format.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(country))
+    })
+}
+
+// Format for the identity's country, the generic one when the country isn't known
+pub fn address_format(entry: &Value) -> &'static AddressFormat {
+    find_country(entry["country"].as_str().unwrap_or("")).unwrap_or(&GENERIC_FORMAT)
+}
+
+fn matches_pattern(value: &str, pattern: &str) -> bool {
+    value.chars().count() == pattern.chars().count()
+        && value.chars().zip(pattern.chars()).all(|(c, p)| match p {
+            'N' => c.is_ascii_digit(),
+            'A' => c.is_ascii_alphabetic(),
+            '?' => c.is_ascii_alphanumeric(),
+            _ => c == p,
+        })
+}
+
+pub fn validate_postal_code(postal_code: &str, format: &AddressFormat) -> Result<(), IdentityFieldError> {
+    let postal_code = postal_code.trim().to_ascii_uppercase();
+    if format.postal_patterns.is_empty() || format.postal_patterns.iter().any(|pattern| matches_pattern(&postal_code, pattern)) {
+        Ok(())
+    } else {
+        Err(IdentityFieldError::InvalidPostalCode(format.postal_label))
+    }
+}
+
+pub fn validate_region(region: &str, format: &AddressFormat) -> Result<(), IdentityFieldError> {
+    let region = region.trim();
+    if format.regions.is_empty() || format.regions.iter().any(|code| code.eq_ignore_ascii_case(region)) {
+        Ok(())
+    } else {
+        Err(IdentityFieldError::UnknownRegion(format.region_label))
+    }
+}
+
+// Phone number in E.164 form, "+" and up to 15 digits. National numbers get the
+// calling code of the identity's country in place of their trunk prefix.
+pub fn normalize_phone(phone: &str, format: &AddressFormat) -> Result<String, IdentityFieldError> {
+    let phone = phone.trim();
+    if phone.chars().any(|c| !(c.is_ascii_digit() || " -./()".contains(c) || c == '+')) || phone.rfind('+').is_some_and(|i| i > 0) {
+        return Err(IdentityFieldError::InvalidCharacters);
+    }
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    let international = if phone.starts_with('+') {
+        digits
+    } else if let Some(rest) = digits.strip_prefix("00") {
+        rest.to_string()
+    } else if let (Some(rest), "1") = (digits.strip_prefix("011"), format.calling_code) {
+        rest.to_string()
+    } else if format.calling_code.is_empty() {
+        return Err(IdentityFieldError::MissingCountryCode);
+    } else {
+        // NANP numbers are 10 digits, an extra leading 1 is the trunk prefix
+        let national = match digits.strip_prefix(format.trunk_prefix) {
+            Some(rest) if !format.trunk_prefix.is_empty() && (format.calling_code != "1" || digits.len() == 11) => rest,
+            _ => digits.as_str(),
+        };
+        format!("{}{}", format.calling_code, national)
+    };
+
+    if !(8..=15).contains(&international.len()) {
+        return Err(IdentityFieldError::InvalidLength);
+    }
+    if international.starts_with('0') {
+        return Err(IdentityFieldError::MissingCountryCode);
+    }
+    Ok(format!("+{}", international))
+}
+
+// Plausible email address: a local part, an @ and a dotted domain of letters, digits and hyphens
+pub fn is_email(value: &str) -> bool {
+    let value = value.trim();
+    let Some((local, domain)) = value.rsplit_once('@') else {
+        return false;
+    };
+    let labels: Vec<&str> = domain.split('.').collect();
+    !local.is_empty()
+        && local.len() <= 64
+        && value.len() <= 254
+        && !value.contains(char::is_whitespace)
+        && labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+        && labels.last().is_some_and(|tld| tld.len() >= 2 && tld.chars().all(char::is_alphabetic))
+}
+
+pub fn validate_email(email: &str) -> Result<(), IdentityFieldError> {
+    if is_email(email) {
+        Ok(())
+    } else {
+        Err(IdentityFieldError::InvalidEmail)
+    }
+}
+
+// Street line, with the apartment after the street address
+fn street(entry: &Value) -> String {
+    let address = entry["address"].as_str().unwrap_or("").trim();
+    match entry["apt_number"].as_str().unwrap_or("").trim() {
+        "" => address.to_string(),
+        apt => format!("{}, {}", address, apt),
+    }
+}
+
+// Postal address laid out the way the identity's country writes it
+pub fn format_address(entry: &Value) -> String {
+    let format = address_format(entry);
+    let country = match find_country(entry["country"].as_str().unwrap_or("")) {
+        Some(country) => country.name.to_string(),
+        None => entry["country"].as_str().unwrap_or("").trim().to_string(),
+    };
+    let text = |key: &str| entry[key].as_str().unwrap_or("").trim().to_string();
+
+    format.lines
+        .iter()
+        .map(|line| {
+            let line = line
+                .replace("{street}", &street(entry))
+                .replace("{city}", &text("city"))
+                .replace("{region}", &text("state"))
+                .replace("{postal}", &text("zipcode").to_ascii_uppercase())
+                .replace("{country}", &country);
+            // Separators around fields that are left empty
+            line.split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ")
+                .trim_matches(|c: char| c == ',' || c == '-' || c.is_whitespace())
+                .to_string()
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Identities saved by older versions stored numbers as JSON numbers, which drops leading
+// zeros, and countries as free text
+pub fn normalize_identity(entry: &mut Value) {
+    if let Some(country) = find_country(entry["country"].as_str().unwrap_or("")) {
+        entry["country"] = Value::from(country.code);
+    }
+    let format = address_format(entry);
+    if let Value::Number(zipcode) = &entry["zipcode"] {
+        let mut zipcode = zipcode.to_string();
+        // All-digit postal codes have a fixed length, put back the zeros that were lost
+        if let Some(length) = format.postal_patterns.first().filter(|p| p.chars().all(|c| c == 'N')).map(|p| p.len()) {
+            zipcode = format!("{:0>width$}", zipcode, width = length);
+        }
+        entry["zipcode"] = Value::String(zipcode);
+    }
+    if let Value::Number(phone) = &entry["phone"] {
+        entry["phone"] = Value::String(phone.to_string());
+    }
+}
+
+// Errors raised when validating identity fields
+#[derive(Debug)]
+pub enum IdentityFieldError {
+    InvalidCharacters,
+    InvalidLength,
+    MissingCountryCode,
+    InvalidPostalCode(&'static str),
+    UnknownRegion(&'static str),
+    InvalidEmail,
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for IdentityFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdentityFieldError::InvalidCharacters => write!(f, "Contains invalid characters"),
+            IdentityFieldError::InvalidLength => write!(f, "Has the wrong number of digits"),
+            IdentityFieldError::MissingCountryCode => write!(f, "Add a country or start with + and the country code"),
+            IdentityFieldError::InvalidPostalCode(label) => write!(f, "Not a valid {}", label),
+            IdentityFieldError::UnknownRegion(label) => write!(f, "Unknown {}, use its abbreviation", label),
+            IdentityFieldError::InvalidEmail => write!(f, "Not a valid email address"),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub mod banking;
+pub mod card;
+pub mod custom_field;
+pub mod date;
+pub mod document;
+pub mod entry;
+pub mod expiry;
+pub mod folder;
+pub mod identity;
+pub mod schema;
+pub mod search;
+pub mod sort;
+pub mod vcard;
+pub mod wifi;
\ No newline at end of file
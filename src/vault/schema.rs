@@ -0,0 +1,390 @@
+use serde_json::Value;
+use std::fmt;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::secure::ssh;
+use crate::vault::banking;
+use crate::vault::card;
+use crate::vault::date::parse_date;
+use crate::vault::identity;
+use crate::vault::wifi::WifiSecurity;
+
+// Checks a field value, the error is shown next to the field
+pub type Validator = fn(&str, &Value) -> Result<(), String>;
+
+/// How a field is stored, edited and shown in the details pane
+#[derive(Clone, Copy, Debug)]
+pub enum FieldKind {
+    /// Single line of plain text
+    Text,
+    /// Encrypted at rest, hidden until the entry's secrets are revealed
+    Secret,
+    /// Encrypted multi-line text, edited one line per input
+    SecretLines,
+    /// Login password: a secret edited with the generator and strength meter
+    Password,
+    /// otpauth URI, shown as a live one-time code
+    Otp,
+    /// "YYYY-MM-DD" date, flagged as it gets close
+    Expiry,
+    /// "MM/YY" card expiry, valid until the end of that month
+    CardExpiry,
+    /// One of a fixed set of stored values
+    Choice(fn() -> Vec<FieldChoice>),
+    /// Yes or no
+    Flag,
+    /// Filled in by the app, e.g. from a generated key, and never typed in
+    Derived,
+    /// Derived and encrypted at rest
+    DerivedSecret,
+}
+
+impl FieldKind {
+    // Fields encrypted by encrypt_sensitive_fields
+    pub fn is_secret(&self) -> bool {
+        matches!(self, FieldKind::Secret | FieldKind::SecretLines | FieldKind::Password | FieldKind::DerivedSecret)
+    }
+
+    // Fields search can match, otpauth URIs carry the TOTP secret
+    pub fn is_searchable(&self) -> bool {
+        !self.is_secret() && !matches!(self, FieldKind::Otp | FieldKind::Flag)
+    }
+
+    pub fn is_derived(&self) -> bool {
+        matches!(self, FieldKind::Derived | FieldKind::DerivedSecret)
+    }
+}
+
+/// An option of a `FieldKind::Choice` field
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChoice {
+    /// Value stored in the entry
+    pub value: &'static str,
+    pub label: String,
+}
+
+impl fmt::Display for FieldChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// A field of an entry type, types list them in the order they're shown
+pub struct FieldSpec {
+    pub key: &'static str,
+    pub label: &'static str,
+    /// Label that depends on other fields, e.g. "ZIP Code" or "Postcode" by country
+    pub dynamic_label: Option<fn(&Value) -> &'static str>,
+    pub kind: FieldKind,
+    /// Offer a copy button next to the value
+    pub copy: bool,
+    /// Matched by search, off for long values like keys that would match any query
+    pub searchable: bool,
+    /// Expected format, added to the input's placeholder
+    pub hint: Option<&'static str>,
+    /// Reason a value is invalid, empty values are never checked
+    pub validate: Option<Validator>,
+    /// Display form of a stored value, e.g. IBANs in groups of four
+    pub format: Option<fn(&str) -> String>,
+    /// The field is only shown and edited while this holds for the entry
+    pub shown_if: Option<fn(&Value) -> bool>,
+    /// Keeps dependent fields in sync once the field has changed
+    pub on_change: Option<fn(&mut Value)>,
+    /// Rewrites the value into its stored form when the entry is saved, not while typing
+    pub on_save: Option<fn(&mut Value)>,
+}
+
+impl FieldSpec {
+    const fn new(key: &'static str, label: &'static str, kind: FieldKind) -> FieldSpec {
+        FieldSpec { key, label, dynamic_label: None, kind, copy: false, searchable: true, hint: None, validate: None, format: None, shown_if: None, on_change: None, on_save: None }
+    }
+
+    const fn relabel(mut self, dynamic_label: fn(&Value) -> &'static str) -> FieldSpec {
+        self.dynamic_label = Some(dynamic_label);
+        self
+    }
+
+    const fn copyable(mut self) -> FieldSpec {
+        self.copy = true;
+        self
+    }
+
+    const fn unsearchable(mut self) -> FieldSpec {
+        self.searchable = false;
+        self
+    }
+
+    const fn hint(mut self, hint: &'static str) -> FieldSpec {
+        self.hint = Some(hint);
+        self
+    }
+
+    const fn validate(mut self, validate: Validator) -> FieldSpec {
+        self.validate = Some(validate);
+        self
+    }
+
+    const fn format(mut self, format: fn(&str) -> String) -> FieldSpec {
+        self.format = Some(format);
+        self
+    }
+
+    const fn shown_if(mut self, shown_if: fn(&Value) -> bool) -> FieldSpec {
+        self.shown_if = Some(shown_if);
+        self
+    }
+
+    const fn on_change(mut self, on_change: fn(&mut Value)) -> FieldSpec {
+        self.on_change = Some(on_change);
+        self
+    }
+
+    const fn on_save(mut self, on_save: fn(&mut Value)) -> FieldSpec {
+        self.on_save = Some(on_save);
+        self
+    }
+
+    pub fn label_for(&self, entry: &Value) -> &'static str {
+        self.dynamic_label.map_or(self.label, |dynamic_label| dynamic_label(entry))
+    }
+
+    // Value of the field in a blank entry
+    pub fn default_value(&self) -> Value {
+        match self.kind {
+            FieldKind::Flag => Value::Bool(false),
+            FieldKind::Choice(choices) => Value::from(choices().first().map(|choice| choice.value).unwrap_or("")),
+            _ => Value::String(String::new()),
+        }
+    }
+
+    pub fn is_shown(&self, entry: &Value) -> bool {
+        self.shown_if.is_none_or(|shown_if| shown_if(entry))
+    }
+
+    // Other fields of the entry are passed along, e.g. the card brand decides the security code length
+    pub fn error(&self, value: &str, entry: &Value) -> Option<String> {
+        if value.trim().is_empty() {
+            return None;
+        }
+        if matches!(self.kind, FieldKind::Expiry) && parse_date(value).is_none() {
+            return Some(String::from("Use the YYYY-MM-DD format"));
+        }
+        self.validate.and_then(|validate| validate(value, entry).err())
+    }
+
+    // Last valid day of an expiry field as "YYYY-MM-DD", None for other fields and unset dates
+    pub fn expires_on(&self, value: &str) -> Option<String> {
+        match self.kind {
+            FieldKind::Expiry => parse_date(value).map(|(year, month, day)| format!("{:04}-{:02}-{:02}", year, month, day)),
+            FieldKind::CardExpiry => card::expires_on(value),
+            _ => None,
+        }
+    }
+
+    pub fn display(&self, value: &str) -> String {
+        match self.format {
+            Some(format) if !value.trim().is_empty() => format(value),
+            _ => value.to_string(),
+        }
+    }
+}
+
+const PASSWORD_FIELDS: &[FieldSpec] = &[
+    FieldSpec::new("title", "Title", FieldKind::Text),
+    FieldSpec::new("url", "URL", FieldKind::Text),
+    FieldSpec::new("username", "Username", FieldKind::Text).copyable(),
+    FieldSpec::new("password", "Password", FieldKind::Password),
+    FieldSpec::new("otpauth", "OTP Auth", FieldKind::Otp),
+    FieldSpec::new("notes", "Notes", FieldKind::Text),
+];
+
+const IDENTITY_FIELDS: &[FieldSpec] = &[
+    FieldSpec::new("title", "Title", FieldKind::Text),
+    FieldSpec::new("first_name", "First Name", FieldKind::Text),
+    FieldSpec::new("middle_initial", "Middle Initial", FieldKind::Text),
+    FieldSpec::new("last_name", "Last Name", FieldKind::Text),
+    // The country decides the labels and formats of the address fields below it
+    FieldSpec::new("country", "Country", FieldKind::Choice(country_choices)),
+    FieldSpec::new("address", "Address", FieldKind::Text),
+    FieldSpec::new("apt_number", "Apt Number", FieldKind::Text),
+    FieldSpec::new("city", "City", FieldKind::Text),
+    FieldSpec::new("state", "State", FieldKind::Text).relabel(region_label).validate(region).shown_if(has_region),
+    FieldSpec::new("zipcode", "Zipcode", FieldKind::Text).relabel(postal_label).validate(postal_code),
+    FieldSpec::new("phone", "Phone", FieldKind::Text).copyable().hint("+1 555 123 4567").validate(phone).on_save(normalize_phone),
+    FieldSpec::new("email", "Email", FieldKind::Text).copyable().validate(email),
+];
+
+const CARD_FIELDS: &[FieldSpec] = &[
+    FieldSpec::new("title", "Title", FieldKind::Text),
+    FieldSpec::new("name", "Cardholder Name", FieldKind::Text),
+    FieldSpec::new("card_number", "Card Number", FieldKind::Text)
+        .copyable()
+        .unsearchable()
+        .validate(card_number)
+        .format(card::format_card_number)
+        .on_change(card::normalize_card),
+    FieldSpec::new("card_last_four", "Last Four", FieldKind::Derived),
+    FieldSpec::new("expiration_date", "Expiration Date", FieldKind::CardExpiry).hint("MM/YY").validate(card_expiry),
+    FieldSpec::new("security_code", "Security Code", FieldKind::Text).unsearchable().validate(card_security_code),
+];
+
+const NOTE_FIELDS: &[FieldSpec] = &[
+    FieldSpec::new("title", "Title", FieldKind::Text),
+    FieldSpec::new("body", "Body", FieldKind::SecretLines),
+];
+
+const SSH_KEY_FIELDS: &[FieldSpec] = &[
+    FieldSpec::new("title", "Title", FieldKind::Text),
+    FieldSpec::new("comment", "Comment", FieldKind::Text).on_change(sync_ssh_comment),
+    FieldSpec::new("fingerprint", "Fingerprint", FieldKind::Derived),
+    FieldSpec::new("public_key", "Public Key", FieldKind::Derived).copyable().unsearchable(),
+    FieldSpec::new("private_key", "Private Key", FieldKind::DerivedSecret),
+];
+
+const API_CREDENTIAL_FIELDS: &[FieldSpec] = &[
+    FieldSpec::new("title", "Title", FieldKind::Text),
+    FieldSpec::new("service", "Service", FieldKind::Text),
+    FieldSpec::new("key_id", "Key ID", FieldKind::Text).copyable(),
+    FieldSpec::new("secret", "Secret", FieldKind::Secret),
+    FieldSpec::new("scopes", "Scopes", FieldKind::Text),
+    FieldSpec::new("expires", "Expires", FieldKind::Expiry).hint("YYYY-MM-DD"),
+];
+
+const BANK_ACCOUNT_FIELDS: &[FieldSpec] = &[
+    FieldSpec::new("title", "Title", FieldKind::Text),
+    FieldSpec::new("bank_name", "Bank Name", FieldKind::Text),
+    FieldSpec::new("account_number", "Account Number", FieldKind::Text).copyable().validate(account_number),
+    FieldSpec::new("routing_number", "Routing Number", FieldKind::Text).copyable().validate(routing_number),
+    FieldSpec::new("iban", "IBAN", FieldKind::Text).copyable().validate(iban).format(banking::format_iban),
+    FieldSpec::new("swift", "SWIFT/BIC", FieldKind::Text).validate(swift),
+    FieldSpec::new("pin", "PIN", FieldKind::Secret),
+];
+
+const WIFI_FIELDS: &[FieldSpec] = &[
+    FieldSpec::new("title", "Title", FieldKind::Text),
+    FieldSpec::new("ssid", "SSID", FieldKind::Text),
+    FieldSpec::new("security", "Security", FieldKind::Choice(wifi_security_choices)),
+    FieldSpec::new("hidden", "Hidden Network", FieldKind::Flag),
+    // Open networks have no passphrase to store
+    FieldSpec::new("passphrase", "Passphrase", FieldKind::Secret).shown_if(has_wifi_passphrase),
+];
+
+// Fields of an entry type, tags, favorites, folders and custom fields are common to all
+pub fn fields(entry_type: EntryType) -> &'static [FieldSpec] {
+    match entry_type {
+        EntryType::Passwords => PASSWORD_FIELDS,
+        EntryType::Identities => IDENTITY_FIELDS,
+        EntryType::Cards => CARD_FIELDS,
+        EntryType::Notes => NOTE_FIELDS,
+        EntryType::SshKeys => SSH_KEY_FIELDS,
+        EntryType::ApiCredentials => API_CREDENTIAL_FIELDS,
+        EntryType::BankAccounts => BANK_ACCOUNT_FIELDS,
+        EntryType::WifiNetworks => WIFI_FIELDS,
+    }
+}
+
+pub fn field(entry_type: EntryType, key: &str) -> Option<&'static FieldSpec> {
+    fields(entry_type).iter().find(|spec| spec.key == key)
+}
+
+// Put edited values in their stored form, run before the entry is encrypted and saved
+pub fn prepare_for_save(entry_type: EntryType, entry: &mut Value) {
+    for on_save in fields(entry_type).iter().filter_map(|spec| spec.on_save) {
+        on_save(entry);
+    }
+}
+
+fn card_number(value: &str, _entry: &Value) -> Result<(), String> {
+    card::validate_card_number(value).map_err(|e| e.to_string())
+}
+
+fn card_expiry(value: &str, _entry: &Value) -> Result<(), String> {
+    card::validate_expiry(value).map_err(|e| e.to_string())
+}
+
+fn card_security_code(value: &str, entry: &Value) -> Result<(), String> {
+    let brand = card::detect_brand(entry["card_number"].as_str().unwrap_or(""));
+    card::validate_security_code(value, brand).map_err(|e| e.to_string())
+}
+
+fn account_number(value: &str, _entry: &Value) -> Result<(), String> {
+    banking::validate_account_number(value).map_err(|e| e.to_string())
+}
+
+fn routing_number(value: &str, _entry: &Value) -> Result<(), String> {
+    banking::validate_routing_number(value).map_err(|e| e.to_string())
+}
+
+fn iban(value: &str, _entry: &Value) -> Result<(), String> {
+    banking::validate_iban(value).map_err(|e| e.to_string())
+}
+
+fn swift(value: &str, _entry: &Value) -> Result<(), String> {
+    banking::validate_swift(value).map_err(|e| e.to_string())
+}
+
+// Countries with a known address format, "Other" keeps the generic one
+fn country_choices() -> Vec<FieldChoice> {
+    std::iter::once(FieldChoice { value: "", label: String::from("Other") })
+        .chain(identity::ADDRESS_FORMATS.iter().map(|format| FieldChoice { value: format.code, label: format.name.to_string() }))
+        .collect()
+}
+
+fn region_label(entry: &Value) -> &'static str {
+    match identity::address_format(entry).region_label {
+        "" => "Region",
+        label => label,
+    }
+}
+
+fn postal_label(entry: &Value) -> &'static str {
+    identity::address_format(entry).postal_label
+}
+
+// Hidden for countries whose addresses have no region, unless one was already filled in
+fn has_region(entry: &Value) -> bool {
+    !identity::address_format(entry).region_label.is_empty() || !entry["state"].as_str().unwrap_or("").trim().is_empty()
+}
+
+fn region(value: &str, entry: &Value) -> Result<(), String> {
+    identity::validate_region(value, identity::address_format(entry)).map_err(|e| e.to_string())
+}
+
+fn postal_code(value: &str, entry: &Value) -> Result<(), String> {
+    identity::validate_postal_code(value, identity::address_format(entry)).map_err(|e| e.to_string())
+}
+
+fn phone(value: &str, entry: &Value) -> Result<(), String> {
+    identity::normalize_phone(value, identity::address_format(entry)).map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn email(value: &str, _entry: &Value) -> Result<(), String> {
+    identity::validate_email(value).map_err(|e| e.to_string())
+}
+
+// Numbers that can't be normalized are kept as typed, the validator already flags them
+fn normalize_phone(entry: &mut Value) {
+    let phone = entry["phone"].as_str().unwrap_or("").to_string();
+    if let Ok(normalized) = identity::normalize_phone(&phone, identity::address_format(entry)) {
+        entry["phone"] = Value::String(normalized);
+    }
+}
+
+fn wifi_security_choices() -> Vec<FieldChoice> {
+    WifiSecurity::ALL
+        .into_iter()
+        .map(|security| FieldChoice { value: security.as_str(), label: security.to_string() })
+        .collect()
+}
+
+fn has_wifi_passphrase(entry: &Value) -> bool {
+    WifiSecurity::from_stored(entry["security"].as_str().unwrap_or("")) != WifiSecurity::Open
+}
+
+// The comment is part of the public key line, keep both in sync
+fn sync_ssh_comment(entry: &mut Value) {
+    let comment = entry["comment"].as_str().unwrap_or("").to_string();
+    if let Ok(public_key) = ssh::with_comment(entry["public_key"].as_str().unwrap_or(""), &comment) {
+        entry["public_key"] = Value::String(public_key);
+    }
+}
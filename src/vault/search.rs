@@ -0,0 +1,201 @@
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::gui::pages::details_page::EntryType;
+use crate::vault::entry::entry_tags;
+use crate::vault::folder::entry_folder;
+use crate::vault::schema;
+
+// Scoring weights for fuzzy matching
+const SCORE_MATCH: i64 = 1;
+const SCORE_CONSECUTIVE: i64 = 5;
+const SCORE_WORD_START: i64 = 8;
+const SCORE_SUBSTRING: i64 = 20;
+const SCORE_PREFIX: i64 = 10;
+const PENALTY_GAP: i64 = 1;
+
+/// Result of fuzzy matching a pattern against a piece of text
+#[derive(Clone, Debug)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Positions (in chars) of the matched characters, used for highlighting
+    pub indices: Vec<usize>,
+}
+
+/// Narrowing chosen in the navigation column, applied on top of the search query
+pub struct EntryFilter<'a> {
+    pub tags: &'a BTreeSet<String>,
+    /// Selected folder and its subfolders, None shows entries of every folder
+    pub folders: Option<BTreeSet<u64>>,
+}
+
+/// An entry that matched a search query
+#[derive(Clone, Debug)]
+pub struct EntryMatch {
+    pub entry_type: EntryType,
+    /// Position of the entry in its array, as used by `Message::SelectEntry`
+    pub index: usize,
+    pub score: i64,
+    /// Matched char positions for every field that contributed to the match
+    pub highlights: HashMap<&'static str, Vec<usize>>,
+}
+
+// Plain text fields that can be searched for each entry type, taken from its schema.
+// Encrypted fields are never searched so their ciphertext can't be matched.
+pub fn searchable_fields(entry_type: EntryType) -> Vec<&'static str> {
+    schema::fields(entry_type)
+        .iter()
+        .filter(|spec| spec.searchable && spec.kind.is_searchable())
+        .map(|spec| spec.key)
+        .chain(["tags"])
+        .collect()
+}
+
+// Text shown for a field, whatever JSON type it was stored as
+pub fn field_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(items) => items.iter().map(field_text).collect::<Vec<String>>().join(", "),
+        _ => String::new(),
+    }
+}
+
+// Case-insensitive fuzzy match: every pattern character must appear in order.
+// Contiguous substrings are preferred, then consecutive characters and word starts.
+// Characters are lowercased one to one so the indices line up with the text's characters.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let lowercase = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let pattern: Vec<char> = pattern.chars().map(lowercase).collect();
+    let text: Vec<char> = text.chars().map(lowercase).collect();
+    if pattern.is_empty() || pattern.len() > text.len() {
+        return None;
+    }
+
+    let is_word_start = |i: usize| i == 0 || !text[i - 1].is_alphanumeric();
+
+    // Exact substring, preferring one that starts a word
+    let substring_starts: Vec<usize> = (0..=text.len() - pattern.len())
+        .filter(|start| text[*start..*start + pattern.len()] == pattern[..])
+        .collect();
+    if let Some(start) = substring_starts.iter().find(|s| is_word_start(**s)).or(substring_starts.first()) {
+        let mut score = SCORE_SUBSTRING + pattern.len() as i64 * (SCORE_MATCH + SCORE_CONSECUTIVE);
+        if *start == 0 {
+            score += SCORE_PREFIX;
+        }
+        if is_word_start(*start) {
+            score += SCORE_WORD_START;
+        }
+        return Some(FuzzyMatch { score, indices: (*start..*start + pattern.len()).collect() });
+    }
+
+    // Subsequence, taking the leftmost occurrence of every character
+    let mut indices = Vec::with_capacity(pattern.len());
+    let mut position = 0;
+    for c in &pattern {
+        let next = (position..text.len()).find(|i| text[*i] == *c)?;
+        indices.push(next);
+        position = next + 1;
+    }
+
+    let mut score = 0;
+    for (n, i) in indices.iter().enumerate() {
+        score += SCORE_MATCH;
+        if is_word_start(*i) {
+            score += SCORE_WORD_START;
+        }
+        if n > 0 {
+            let gap = i - indices[n - 1] - 1;
+            if gap == 0 {
+                score += SCORE_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP * gap as i64;
+            }
+        }
+    }
+    Some(FuzzyMatch { score, indices })
+}
+
+// Match a single entry: every whitespace separated term has to match one of its fields
+pub fn match_entry(entry: &Value, entry_type: EntryType, index: usize, query: &str) -> Option<EntryMatch> {
+    let mut entry_match = EntryMatch {
+        entry_type,
+        index,
+        score: 0,
+        highlights: HashMap::new(),
+    };
+
+    for term in query.split_whitespace() {
+        let (field, best) = searchable_fields(entry_type)
+            .iter()
+            .filter_map(|field| fuzzy_match(term, &field_text(&entry[*field])).map(|m| (*field, m)))
+            .max_by_key(|(_, m)| m.score)?;
+        entry_match.score += best.score;
+        let highlight = entry_match.highlights.entry(field).or_default();
+        highlight.extend(best.indices);
+        highlight.sort_unstable();
+        highlight.dedup();
+    }
+    Some(entry_match)
+}
+
+// Search all entries of a type, best matches first.
+// An empty query matches everything in the original order.
+pub fn search_entries(entries: &Value, entry_type: EntryType, query: &str) -> Vec<EntryMatch> {
+    let mut matches: Vec<EntryMatch> = entries
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| match_entry(entry, entry_type, index, query))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if !query.trim().is_empty() {
+        matches.sort_by(|a, b| b.score.cmp(&a.score).then(a.index.cmp(&b.index)));
+    }
+    matches
+}
+
+// Keep only the entries carrying every one of the selected tags
+pub fn filter_by_tags(entries: &Value, matches: Vec<EntryMatch>, selected_tags: &BTreeSet<String>) -> Vec<EntryMatch> {
+    if selected_tags.is_empty() {
+        return matches;
+    }
+    matches
+        .into_iter()
+        .filter(|found| {
+            let tags = entry_tags(&entries[found.index]);
+            selected_tags.iter().all(|selected| tags.contains(selected))
+        })
+        .collect()
+}
+
+// Keep only the entries inside the selected folder tree
+pub fn filter_by_folder(entries: &Value, matches: Vec<EntryMatch>, folders: &Option<BTreeSet<u64>>) -> Vec<EntryMatch> {
+    match folders {
+        None => matches,
+        Some(folders) => matches
+            .into_iter()
+            .filter(|found| entry_folder(&entries[found.index]).is_some_and(|folder| folders.contains(&folder)))
+            .collect(),
+    }
+}
+
+pub fn filter_entries(entries: &Value, matches: Vec<EntryMatch>, filter: &EntryFilter) -> Vec<EntryMatch> {
+    filter_by_folder(entries, filter_by_tags(entries, matches, filter.tags), &filter.folders)
+}
+
+// Search passwords, identities and cards at once
+pub fn search_all(vault: &Value, query: &str) -> Vec<EntryMatch> {
+    let mut matches: Vec<EntryMatch> = EntryType::ALL
+        .into_iter()
+        .flat_map(|entry_type| search_entries(&vault[entry_type.as_str()], entry_type, query))
+        .collect();
+    matches.sort_by_key(|m| Reverse(m.score));
+    matches
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::vault::entry::{entry_tags, timestamp};
+use crate::vault::search::{field_text, EntryMatch};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SortOrder {
+    #[default]
+    Title,
+    RecentlyModified,
+    RecentlyUsed,
+    Created,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 4] = [
+        SortOrder::Title,
+        SortOrder::RecentlyModified,
+        SortOrder::RecentlyUsed,
+        SortOrder::Created,
+    ];
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortOrder::Title => write!(f, "Title"),
+            SortOrder::RecentlyModified => write!(f, "Recently modified"),
+            SortOrder::RecentlyUsed => write!(f, "Recently used"),
+            SortOrder::Created => write!(f, "Newest first"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Grouping {
+    #[default]
+    None,
+    FirstLetter,
+    Tag,
+}
+
+impl Grouping {
+    pub const ALL: [Grouping; 3] = [Grouping::None, Grouping::FirstLetter, Grouping::Tag];
+}
+
+impl fmt::Display for Grouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Grouping::None => write!(f, "No grouping"),
+            Grouping::FirstLetter => write!(f, "Group by letter"),
+            Grouping::Tag => write!(f, "Group by tag"),
+        }
+    }
+}
+
+/// How a list page orders and groups its entries
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct SortPreference {
+    pub order: SortOrder,
+    pub grouping: Grouping,
+}
+
+// Sort and group matched entries for display.
+// While searching, matches keep their relevance order and are only grouped.
+pub fn arrange(entries: &Value, mut matches: Vec<EntryMatch>, preference: SortPreference, searching: bool) -> Vec<(String, Vec<EntryMatch>)> {
+    if !searching {
+        sort_matches(entries, &mut matches, preference.order);
+    }
+
+    match preference.grouping {
+        Grouping::None => vec![(String::new(), matches)],
+        Grouping::FirstLetter => {
+            let mut groups: BTreeMap<String, Vec<EntryMatch>> = BTreeMap::new();
+            for found in matches {
+                let letter = field_text(&entries[found.index]["title"])
+                    .chars()
+                    .next()
+                    .filter(|c| c.is_alphabetic())
+                    .map(|c| c.to_uppercase().to_string())
+                    .unwrap_or_else(|| String::from("#"));
+                groups.entry(letter).or_default().push(found);
+            }
+            groups.into_iter().collect()
+        },
+        Grouping::Tag => {
+            // Entries with several tags appear under each of them
+            let mut groups: BTreeMap<String, Vec<EntryMatch>> = BTreeMap::new();
+            let mut untagged = Vec::new();
+            for found in matches {
+                let tags = entry_tags(&entries[found.index]);
+                if tags.is_empty() {
+                    untagged.push(found);
+                    continue;
+                }
+                for tag in tags {
+                    groups.entry(tag).or_default().push(found.clone());
+                }
+            }
+            let mut groups: Vec<(String, Vec<EntryMatch>)> = groups.into_iter().collect();
+            if !untagged.is_empty() {
+                groups.push((String::from("Untagged"), untagged));
+            }
+            groups
+        },
+    }
+}
+
+fn sort_matches(entries: &Value, matches: &mut [EntryMatch], order: SortOrder) {
+    match order {
+        // Case-insensitive, with untitled entries last
+        SortOrder::Title => matches.sort_by_key(|found| {
+            let title = field_text(&entries[found.index]["title"]).to_lowercase();
+            (title.is_empty(), title)
+        }),
+        SortOrder::RecentlyModified => matches.sort_by_key(|found| Reverse(timestamp(&entries[found.index], "modified"))),
+        SortOrder::RecentlyUsed => matches.sort_by_key(|found| Reverse(timestamp(&entries[found.index], "last_used"))),
+        SortOrder::Created => matches.sort_by_key(|found| Reverse(timestamp(&entries[found.index], "created"))),
+    }
+}
@@ -0,0 +1,293 @@
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::io;
+
+use crate::gui::pages::details_page::EntryType;
+use crate::vault::entry::new_entry;
+use crate::vault::identity::{address_format, find_country, normalize_identity, normalize_phone};
+use crate::vault::search::field_text;
+
+// Content lines longer than this many octets are folded onto continuation lines
+const MAX_LINE_OCTETS: usize = 75;
+
+// Escape TEXT values, ';' and ',' separate components and lists
+fn escape(value: &str) -> String {
+    value
+        .trim()
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, c == '\\') {
+            (_, true) => match chars.next() {
+                Some('n') | Some('N') => unescaped.push('\n'),
+                Some(other) => unescaped.push(other),
+                None => unescaped.push('\\'),
+            },
+            (c, false) => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+// Components of a structured value like N or ADR, split on unescaped ';'
+fn components(value: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in value.chars() {
+        if c == ';' && !escaped {
+            parts.push(unescape(&current));
+            current.clear();
+        } else {
+            current.push(c);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    parts.push(unescape(&current));
+    parts
+}
+
+// Fold a content line into CRLF terminated lines of at most 75 octets, without splitting characters
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+// vCard 4.0 of an identity, empty properties are left out
+pub fn to_vcard(entry: &Value) -> String {
+    let text = |key: &str| field_text(&entry[key]).trim().to_string();
+    let full_name = [text("first_name"), text("middle_initial"), text("last_name")]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<String>>()
+        .join(" ");
+    // FN is the only required property
+    let display_name = match (full_name.is_empty(), text("title").is_empty()) {
+        (false, _) => full_name,
+        (true, false) => text("title"),
+        (true, true) => String::from("Unnamed"),
+    };
+
+    let mut lines = vec![
+        String::from("BEGIN:VCARD"),
+        String::from("VERSION:4.0"),
+        format!("FN:{}", escape(&display_name)),
+        format!("N:{};{};{};;", escape(&text("last_name")), escape(&text("first_name")), escape(&text("middle_initial"))),
+    ];
+    let address = ["apt_number", "address", "city", "state", "zipcode"].map(text);
+    let country = match find_country(&text("country")) {
+        Some(country) => country.name.to_string(),
+        None => text("country"),
+    };
+    if address.iter().any(|part| !part.is_empty()) || !country.is_empty() {
+        lines.push(format!(
+            "ADR;TYPE=home:;{};{};{};{};{};{}",
+            escape(&address[0]), escape(&address[1]), escape(&address[2]), escape(&address[3]), escape(&address[4]), escape(&country),
+        ));
+    }
+    let phone = text("phone");
+    if !phone.is_empty() {
+        // TEL is a tel: URI in vCard 4.0, numbers that can't be normalized stay as text
+        lines.push(match normalize_phone(&phone, address_format(entry)) {
+            Ok(e164) => format!("TEL;VALUE=uri;TYPE=voice:tel:{}", e164),
+            Err(_) => format!("TEL;VALUE=text;TYPE=voice:{}", escape(&phone)),
+        });
+    }
+    if !text("email").is_empty() {
+        lines.push(format!("EMAIL:{}", escape(&text("email"))));
+    }
+    lines.push(String::from("END:VCARD"));
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+// Write identities to one .vcf file, returns how many were written
+pub fn export_vcards(entries: &[Value], path: &str) -> Result<usize, VcardError> {
+    if entries.is_empty() {
+        return Err(VcardError::NoCards);
+    }
+    fs::write(path.trim(), entries.iter().map(to_vcard).collect::<String>())?;
+    Ok(entries.len())
+}
+
+/// A content line: name, parameters and raw value
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    // "TYPE=home,work" and the bare "HOME" of older vCards both count
+    fn has_type(&self, wanted: &str) -> bool {
+        self.params.iter().any(|(name, value)| {
+            (name == "TYPE" && value.split(',').any(|t| t.trim_matches('"').eq_ignore_ascii_case(wanted)))
+                || (value.is_empty() && name.eq_ignore_ascii_case(wanted))
+        })
+    }
+
+    fn is_preferred(&self) -> bool {
+        self.has_type("pref") || self.params.iter().any(|(name, _)| name == "PREF")
+    }
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    // The value starts at the first ':' outside a quoted parameter value
+    let mut quoted = false;
+    let colon = line.char_indices().find(|(_, c)| {
+        if *c == '"' {
+            quoted = !quoted;
+        }
+        *c == ':' && !quoted
+    })?.0;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?;
+    // Properties can be grouped, e.g. "item1.TEL"
+    let name = name.rsplit('.').next().unwrap_or(name).to_ascii_uppercase();
+    let params = parts
+        .map(|param| match param.split_once('=') {
+            Some((key, value)) => (key.to_ascii_uppercase(), value.to_string()),
+            None => (param.to_ascii_uppercase(), String::new()),
+        })
+        .collect();
+    Some(Property { name, params, value: value.to_string() })
+}
+
+// The preferred instance of a property, otherwise the first of the wanted type, otherwise the first
+fn pick<'a>(properties: &'a [Property], name: &str, wanted_type: &str) -> Option<&'a Property> {
+    let all: Vec<&Property> = properties.iter().filter(|property| property.name == name).collect();
+    all.iter().find(|property| property.is_preferred())
+        .or_else(|| all.iter().find(|property| property.has_type(wanted_type)))
+        .or_else(|| all.first())
+        .copied()
+}
+
+// Identity entry from the properties of one vCard
+fn to_identity(properties: &[Property], folder: Option<u64>) -> Value {
+    let mut entry = new_entry(EntryType::Identities, folder);
+    let full_name = pick(properties, "FN", "").map(|property| unescape(&property.value)).unwrap_or_default();
+
+    match pick(properties, "N", "") {
+        Some(name) => {
+            let parts = components(&name.value);
+            let part = |i: usize| parts.get(i).map(|part| part.split(',').next().unwrap_or("").trim().to_string()).unwrap_or_default();
+            entry["last_name"] = Value::from(part(0));
+            entry["first_name"] = Value::from(part(1));
+            entry["middle_initial"] = Value::from(part(2).chars().next().map(String::from).unwrap_or_default());
+        },
+        // Without N the formatted name is split on its last space
+        None => {
+            let (first, last) = full_name.trim().rsplit_once(' ').unwrap_or((full_name.trim(), ""));
+            entry["first_name"] = Value::from(first.trim());
+            entry["last_name"] = Value::from(last.trim());
+        },
+    }
+    entry["title"] = Value::from(full_name.trim());
+
+    if let Some(address) = pick(properties, "ADR", "home") {
+        let parts = components(&address.value);
+        let part = |i: usize| parts.get(i).map(|part| part.trim().to_string()).unwrap_or_default();
+        // Multiple street lines come as a list or on separate lines
+        entry["apt_number"] = Value::from(part(1));
+        entry["address"] = Value::from(part(2).replace('\n', ", "));
+        entry["city"] = Value::from(part(3));
+        entry["state"] = Value::from(part(4));
+        entry["zipcode"] = Value::from(part(5));
+        entry["country"] = Value::from(part(6));
+    }
+    if let Some(phone) = pick(properties, "TEL", "cell") {
+        let value = unescape(&phone.value);
+        entry["phone"] = Value::from(value.trim().trim_start_matches("tel:"));
+    }
+    if let Some(email) = pick(properties, "EMAIL", "home") {
+        entry["email"] = Value::from(unescape(&email.value).trim().trim_start_matches("mailto:"));
+    }
+    normalize_identity(&mut entry);
+    entry
+}
+
+// Identities from the vCards in a .vcf file's text, vCard 3.0 files are accepted too
+pub fn parse_vcards(text: &str, folder: Option<u64>) -> Result<Vec<Value>, VcardError> {
+    // Unfold continuation lines, they start with a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut identities = Vec::new();
+    let mut card: Option<(usize, Vec<Property>)> = None;
+    for (number, line) in lines.iter().enumerate() {
+        let Some(property) = parse_property(line) else {
+            continue;
+        };
+        match (property.name.as_str(), card.take()) {
+            ("BEGIN", None) if property.value.eq_ignore_ascii_case("VCARD") => card = Some((number + 1, Vec::new())),
+            ("BEGIN", Some((start, _))) => return Err(VcardError::Unterminated(start)),
+            ("END", Some((_, properties))) if property.value.eq_ignore_ascii_case("VCARD") => identities.push(to_identity(&properties, folder)),
+            (_, Some((start, mut properties))) => {
+                properties.push(property);
+                card = Some((start, properties));
+            },
+            (_, None) => {},
+        }
+    }
+    if let Some((start, _)) = card {
+        return Err(VcardError::Unterminated(start));
+    }
+    if identities.is_empty() {
+        return Err(VcardError::NoCards);
+    }
+    Ok(identities)
+}
+
+pub fn import_vcards(path: &str, folder: Option<u64>) -> Result<Vec<Value>, VcardError> {
+    parse_vcards(&fs::read_to_string(path.trim())?, folder)
+}
+
+// Errors raised when reading or writing vCards
+#[derive(Debug)]
+pub enum VcardError {
+    Io(io::Error),
+    NoCards,
+    /// A vCard starting on this line has no END:VCARD
+    Unterminated(usize),
+}
+
+impl From<io::Error> for VcardError {
+    fn from(err: io::Error) -> VcardError {
+        VcardError::Io(err)
+    }
+}
+
+// Implement the Display trait for the custom error type.
+impl fmt::Display for VcardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VcardError::Io(e) => write!(f, "IO error: {}", e),
+            VcardError::NoCards => write!(f, "No vCards found"),
+            VcardError::Unterminated(line) => write!(f, "The vCard starting on line {} has no END:VCARD", line),
+        }
+    }
+}
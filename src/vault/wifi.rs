@@ -0,0 +1,69 @@
+use std::fmt;
+
+/// Security of a Wi-Fi network, as used in the `WIFI:` sharing payload
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WifiSecurity {
+    Wpa,
+    Wpa3,
+    Wep,
+    Open,
+}
+
+impl WifiSecurity {
+    pub const ALL: [WifiSecurity; 4] = [WifiSecurity::Wpa, WifiSecurity::Wpa3, WifiSecurity::Wep, WifiSecurity::Open];
+
+    // Value stored in the entry
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WifiSecurity::Wpa => "WPA",
+            WifiSecurity::Wpa3 => "SAE",
+            WifiSecurity::Wep => "WEP",
+            WifiSecurity::Open => "nopass",
+        }
+    }
+
+    // Unknown values fall back to WPA, by far the most common
+    pub fn from_stored(value: &str) -> WifiSecurity {
+        WifiSecurity::ALL
+            .into_iter()
+            .find(|security| security.as_str().eq_ignore_ascii_case(value))
+            .unwrap_or(WifiSecurity::Wpa)
+    }
+}
+
+impl fmt::Display for WifiSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            WifiSecurity::Wpa => "WPA/WPA2",
+            WifiSecurity::Wpa3 => "WPA3",
+            WifiSecurity::Wep => "WEP",
+            WifiSecurity::Open => "None",
+        })
+    }
+}
+
+// Payload understood by phone cameras when encoded as a QR code,
+// e.g. WIFI:T:WPA;S:Home;P:secret;;
+pub fn wifi_payload(ssid: &str, security: WifiSecurity, passphrase: &str, hidden: bool) -> String {
+    let mut payload = format!("WIFI:T:{};S:{};", security.as_str(), escape(ssid));
+    if security != WifiSecurity::Open {
+        payload.push_str(&format!("P:{};", escape(passphrase)));
+    }
+    if hidden {
+        payload.push_str("H:true;");
+    }
+    payload.push(';');
+    payload
+}
+
+// Special characters in the SSID and passphrase are backslash escaped
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}